@@ -0,0 +1,183 @@
+///
+/// # `amend.rs`
+/// Contains the functions used to fix up previous commits:
+/// `commit --amend`, `commit --fixup`, `commit --squash` and `autosquash`.
+// Imports ================================================================================= Imports
+use std::path::Path;
+
+use dialoguer::Select;
+
+use crate::draft::build_amend_draft;
+use crate::git_related::{
+    get_commit_files, get_commit_messages, get_log_entries, get_merge_base, parse_status_line,
+    read_git_status, rebase_autosquash, LogEntry,
+};
+use crate::my_theme::ColorfulTheme;
+
+// Constants  ===========================================================================  Constants
+/// Number of commits listed when the branch has no commit of its own
+const RECENT_COMMITS: &str = "20";
+
+// Functions  ===========================================================================  Functions
+///
+/// # `write_amend_draft`
+/// Regenerates the draft from the `HEAD` message merged with the new changes.
+///
+/// ## Arguments
+/// * `path` - `&Path` - The draft file
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the write
+pub fn write_amend_draft(path: &Path) -> Result<(), String> {
+    let head_message = get_commit_messages("-1")
+        .into_iter()
+        .next()
+        .ok_or("There is no commit to amend.")?;
+
+    let mut files = get_commit_files("HEAD");
+    for (_, file) in read_git_status().lines().filter_map(parse_status_line) {
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+
+    std::fs::write(path, build_amend_draft(&head_message, &files)).map_err(|e| e.to_string())
+}
+
+///
+/// # `branch_commits`
+/// Returns the commits of the current branch, or the recent ones if the branch
+/// has no commit of its own.
+///
+/// ## Arguments
+/// * `base_branch` - `&str` - The branch the current branch starts from
+///
+/// ## Returns
+/// * `Vec<LogEntry>` - The commits, newest first
+fn branch_commits(base_branch: &str) -> Vec<LogEntry> {
+    let commits = get_merge_base(base_branch)
+        .map(|merge_base| get_log_entries(&[&format!("{merge_base}..HEAD")]))
+        .unwrap_or_default();
+
+    if commits.is_empty() {
+        get_log_entries(&["-n", RECENT_COMMITS])
+    } else {
+        commits
+    }
+}
+
+///
+/// # `pick_commit`
+/// Lets the user pick a commit of the current branch.
+///
+/// ## Arguments
+/// * `base_branch` - `&str` - The branch the current branch starts from
+/// * `prompt` - `&str` - The picker prompt
+///
+/// ## Returns
+/// * `Option<LogEntry>` - The commit, `None` if there is no commit
+pub fn pick_commit(base_branch: &str, prompt: &str) -> Option<LogEntry> {
+    let mut commits = branch_commits(base_branch);
+
+    if commits.is_empty() {
+        return None;
+    }
+
+    let items: Vec<String> = commits
+        .iter()
+        .map(|commit| {
+            let mut files = commit
+                .files
+                .iter()
+                .take(3)
+                .cloned()
+                .collect::<Vec<String>>();
+            if commit.files.len() > 3 {
+                files.push(format!("+{}", commit.files.len() - 3));
+            }
+
+            format!(
+                "{} {} ({})",
+                &commit.sha[..7.min(commit.sha.len())],
+                commit.message.lines().next().unwrap_or_default(),
+                files.join(", ")
+            )
+        })
+        .collect();
+
+    let index = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(0)
+        .items(&items)
+        .interact()
+        .unwrap();
+
+    Some(commits.swap_remove(index))
+}
+
+///
+/// # `fixup_message`
+/// Builds the message of a `fixup!` or `squash!` commit, as `git commit --fixup`
+/// and `git commit --squash` would.
+///
+/// ## Arguments
+/// * `target` - `&LogEntry` - The commit to fix up
+/// * `squash` - `bool` - Build a `squash!` message instead of a `fixup!` one
+/// * `draft` - `&str` - The message added to the `squash!` commits
+///
+/// ## Returns
+/// * `String` - The commit message
+pub fn fixup_message(target: &LogEntry, squash: bool, draft: &str) -> String {
+    let subject = target.message.lines().next().unwrap_or_default();
+
+    if squash {
+        format!("squash! {subject}\n\n{}", draft.trim())
+            .trim_end()
+            .to_string()
+    } else {
+        format!("fixup! {subject}")
+    }
+}
+
+///
+/// # `autosquash`
+/// Melds the `fixup!` and `squash!` commits of the branch into their targets.
+///
+/// ## Arguments
+/// * `base_branch` - `&str` - The branch the current branch starts from
+/// * `verbose` - `bool` - Verbose the operation
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the rebase
+pub fn autosquash(base_branch: &str, verbose: bool) -> Result<(), String> {
+    let merge_base =
+        get_merge_base(base_branch).ok_or(format!("No common ancestor with `{base_branch}`."))?;
+
+    rebase_autosquash(&merge_base, verbose)
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::fixup_message;
+    use crate::git_related::LogEntry;
+
+    #[test]
+    fn test_fixup_message() {
+        let target = LogEntry::sample(
+            "[3] (feat on login) Add the form\n\n- `a.rs`:\n\n\tDone",
+            "Tom",
+            "2024-01-01",
+            &["a.rs"],
+        );
+
+        assert_eq!(
+            fixup_message(&target, false, "ignored"),
+            "fixup! [3] (feat on login) Add the form"
+        );
+        assert_eq!(
+            fixup_message(&target, true, "- `a.rs`:\n\n\tFix the label\n"),
+            "squash! [3] (feat on login) Add the form\n\n- `a.rs`:\n\n\tFix the label"
+        );
+    }
+}
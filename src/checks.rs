@@ -0,0 +1,313 @@
+///
+/// # `checks.rs`
+/// Runs the `[[checks]]` configured for the repository before `commit` and `push`.
+///
+/// A check only runs when one of the changed files matches its paths.
+/// The parallel checks run together, the others run one after the other afterwards.
+// Imports ================================================================================= Imports
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use ansi_term::Colour::{Green, Red};
+
+use crate::classify::build_glob_set;
+use crate::config::CheckConfig;
+use crate::git_related::{
+    get_commit_files, get_default_branch, get_diff_files, get_merge_base, get_upstream,
+};
+use crate::scope::{read_cargo_packages, Package};
+
+// Structs  ===============================================================================  Structs
+/// The result of a check.
+#[derive(Debug)]
+pub struct CheckResult {
+    /// The check name
+    pub name: String,
+    /// The check succeeded
+    pub success: bool,
+    /// How long the check took
+    pub duration: Duration,
+    /// The standard output and error of the check
+    pub output: String,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `select_checks`
+/// Selects the checks to run for an operation and the changed files.
+///
+/// ## Arguments
+/// * `checks` - `&[CheckConfig]` - The configured checks
+/// * `operation` - `&str` - `commit` or `push`
+/// * `files` - `&[String]` - The changed files
+///
+/// ## Returns
+/// * `Vec<(&CheckConfig, Vec<String>)>` - The checks with the files matching their paths
+pub fn select_checks<'a>(
+    checks: &'a [CheckConfig],
+    operation: &str,
+    files: &[String],
+) -> Vec<(&'a CheckConfig, Vec<String>)> {
+    checks
+        .iter()
+        .filter(|check| check.on.iter().any(|on| on == operation))
+        .filter_map(|check| {
+            let matching: Vec<String> = if check.paths.is_empty() {
+                files.to_vec()
+            } else {
+                let glob_set = build_glob_set(&check.paths);
+                files
+                    .iter()
+                    .filter(|file| glob_set.is_match(file))
+                    .cloned()
+                    .collect()
+            };
+
+            if matching.is_empty() {
+                None
+            } else {
+                Some((check, matching))
+            }
+        })
+        .collect()
+}
+
+///
+/// # `get_pushed_files`
+/// Returns the files changed by the commits about to be pushed: since the upstream,
+/// or since the default branch when there is no upstream yet.
+///
+/// ## Returns
+/// * `Vec<String>` - The changed files
+pub fn get_pushed_files() -> Vec<String> {
+    let range = get_upstream()
+        .or_else(|| {
+            let default_branch = get_default_branch();
+            get_merge_base(&default_branch).map(|_| default_branch)
+        })
+        .map(|base| format!("{base}...HEAD"));
+
+    match range {
+        Some(range) => get_diff_files(&[&range]),
+        None => get_commit_files("HEAD"),
+    }
+}
+
+///
+/// # `quote`
+/// Quotes a shell argument.
+///
+/// ## Arguments
+/// * `arg` - `&str` - The argument
+///
+/// ## Returns
+/// * `String` - The quoted argument
+fn quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+///
+/// # `expand_command`
+/// Replaces the `{files}` and `{packages}` placeholders of a check command.
+///
+/// ## Arguments
+/// * `command` - `&str` - The check command
+/// * `files` - `&[String]` - The files matching the check
+/// * `packages` - `&[Package]` - The Cargo packages of the workspace
+///
+/// ## Returns
+/// * `String` - The command to run
+pub fn expand_command(command: &str, files: &[String], packages: &[Package]) -> String {
+    let quoted_files: Vec<String> = files.iter().map(|file| quote(file)).collect();
+
+    let mut package_flags: Vec<String> = Vec::new();
+    for file in files {
+        // The deepest package containing the file
+        let package = packages
+            .iter()
+            .filter(|package| Path::new(file).starts_with(&package.path))
+            .max_by_key(|package| package.path.len());
+
+        if let Some(package) = package {
+            let flag = format!("-p {}", quote(&package.name));
+            if !package_flags.contains(&flag) {
+                package_flags.push(flag);
+            }
+        }
+    }
+
+    command
+        .replace("{files}", &quoted_files.join(" "))
+        .replace("{packages}", &package_flags.join(" "))
+}
+
+///
+/// # `run_check`
+/// Runs a check command through the shell.
+///
+/// ## Arguments
+/// * `name` - `&str` - The check name
+/// * `command` - `&str` - The command
+/// * `project_root` - `&Path` - The folder the command runs in
+///
+/// ## Returns
+/// * `CheckResult` - The result of the check
+fn run_check(name: &str, command: &str, project_root: &Path) -> CheckResult {
+    let started = Instant::now();
+
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(project_root)
+        .output();
+
+    let (success, output) = match output {
+        Ok(output) => (
+            output.status.success(),
+            format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ),
+        ),
+        Err(e) => (false, e.to_string()),
+    };
+
+    CheckResult {
+        name: name.to_string(),
+        success,
+        duration: started.elapsed(),
+        output,
+    }
+}
+
+///
+/// # `run_checks`
+/// Runs the checks of an operation and prints a summary.
+///
+/// ## Arguments
+/// * `checks` - `&[CheckConfig]` - The configured checks
+/// * `operation` - `&str` - `commit` or `push`
+/// * `files` - `&[String]` - The changed files
+/// * `project_root` - `&Path` - The project root
+/// * `verbose` - `bool` - Print the output of the successful checks too
+///
+/// ## Returns
+/// * `bool` - `true` if every check succeeded
+pub fn run_checks(
+    checks: &[CheckConfig],
+    operation: &str,
+    files: &[String],
+    project_root: &Path,
+    verbose: bool,
+) -> bool {
+    let selected = select_checks(checks, operation, files);
+
+    if selected.is_empty() {
+        return true;
+    }
+
+    let packages = read_cargo_packages(project_root);
+    let commands: Vec<(&CheckConfig, String)> = selected
+        .iter()
+        .map(|(check, matching)| (*check, expand_command(&check.command, matching, &packages)))
+        .collect();
+
+    println!("Running {} {operation} check(s)...", commands.len());
+
+    let mut results: Vec<CheckResult> = std::thread::scope(|scope| {
+        let handles: Vec<_> = commands
+            .iter()
+            .filter(|(check, _)| check.parallel)
+            .map(|(check, command)| scope.spawn(|| run_check(&check.name, command, project_root)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("a check thread panicked"))
+            .collect()
+    });
+
+    for (check, command) in commands.iter().filter(|(check, _)| !check.parallel) {
+        results.push(run_check(&check.name, command, project_root));
+    }
+
+    for result in &results {
+        let mark = if result.success {
+            Green.bold().paint("✔")
+        } else {
+            Red.bold().paint("✘")
+        };
+
+        println!(
+            "{mark} {} ({:.1}s)",
+            result.name,
+            result.duration.as_secs_f64()
+        );
+
+        if !result.success || verbose {
+            for line in result.output.lines() {
+                println!("    {line}");
+            }
+        }
+    }
+
+    results.iter().all(|result| result.success)
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::{expand_command, select_checks};
+    use crate::config::CheckConfig;
+    use crate::scope::Package;
+
+    #[test]
+    fn test_select_checks() {
+        let checks = vec![
+            CheckConfig {
+                name: "fmt".to_string(),
+                command: "cargo fmt --check".to_string(),
+                paths: vec!["**/*.rs".to_string()],
+                ..CheckConfig::default()
+            },
+            CheckConfig {
+                name: "test".to_string(),
+                command: "cargo test".to_string(),
+                on: vec!["push".to_string()],
+                ..CheckConfig::default()
+            },
+        ];
+        let files = vec!["src/main.rs".to_string(), "README.md".to_string()];
+
+        let selected = select_checks(&checks, "commit", &files);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].0.name, "fmt");
+        assert_eq!(selected[0].1, vec!["src/main.rs"]);
+
+        assert_eq!(select_checks(&checks, "push", &files)[0].1.len(), 2);
+        assert!(select_checks(&checks, "commit", &files[1..]).is_empty());
+    }
+
+    #[test]
+    fn test_expand_command() {
+        let packages = vec![Package {
+            name: "core".to_string(),
+            path: "crates/core".to_string(),
+        }];
+        let files = vec![
+            "crates/core/src/lib.rs".to_string(),
+            "crates/core/src/a b.rs".to_string(),
+        ];
+
+        assert_eq!(
+            expand_command("cargo test {packages}", &files, &packages),
+            "cargo test -p 'core'"
+        );
+        assert_eq!(
+            expand_command("rustfmt --check {files}", &files, &packages),
+            "rustfmt --check 'crates/core/src/lib.rs' 'crates/core/src/a b.rs'"
+        );
+    }
+}
@@ -0,0 +1,184 @@
+///
+/// # `classify.rs`
+/// Suggests a commit type from the changed paths and the diff.
+///
+/// * every changed file matches the globs of a type → that type (`test`, `chore`, ...)
+/// * a new file or a new public item → `feat`
+// Imports ================================================================================= Imports
+use std::collections::BTreeMap;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::Regex;
+
+use crate::git_related::parse_status_line;
+
+// Structs ================================================================================= Structs
+/// A suggested commit type.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    /// The commit type
+    pub commit_type: String,
+    /// Why this type is suggested, on one line
+    pub reason: String,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `build_glob_set`
+/// Builds a glob set, the invalid globs are ignored.
+///
+/// ## Arguments
+/// * `globs` - `&[String]` - The globs
+///
+/// ## Returns
+/// * `GlobSet` - The glob set
+pub fn build_glob_set(globs: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+
+    for glob in globs {
+        match Glob::new(glob) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(e) => eprintln!("Ignoring invalid glob `{glob}`: {e}"),
+        }
+    }
+
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
+}
+
+///
+/// # `find_new_public_item`
+/// Finds a public item added by a diff.
+/// An item whose name is also on a removed line of the same file was only modified or moved.
+///
+/// ## Arguments
+/// * `diff` - `&str` - The diff
+///
+/// ## Returns
+/// * `Option<String>` - The name of the first new public item
+fn find_new_public_item(diff: &str) -> Option<String> {
+    let public_item_rule = Regex::new(
+        r"^([+-])\s*(?:pub(?:\([^)]*\))?\s+(?:async\s+)?(?:fn|struct|enum|trait|mod|const|static|type)|export\s+(?:default\s+)?(?:function|class|const))\s+(\w+)",
+    )
+    .unwrap();
+
+    // Each file starts with its `+++ b/path` line
+    let mut files: Vec<Vec<&str>> = vec![Vec::new()];
+    for line in diff.lines() {
+        if line.starts_with("+++ ") {
+            files.push(Vec::new());
+        } else if let Some(file) = files.last_mut() {
+            file.push(line);
+        }
+    }
+
+    files.iter().find_map(|lines| {
+        let items: Vec<(bool, &str)> = lines
+            .iter()
+            .filter_map(|line| public_item_rule.captures(line))
+            .map(|captures| {
+                (
+                    &captures[1] == "+",
+                    captures.get(2).map_or("", |name| name.as_str()),
+                )
+            })
+            .collect();
+
+        items
+            .iter()
+            .filter(|(added, _)| *added)
+            .find(|(_, name)| !items.contains(&(false, *name)))
+            .map(|(_, name)| (*name).to_string())
+    })
+}
+
+///
+/// # `suggest_commit_type`
+/// Suggests a commit type for the current changes.
+///
+/// ## Arguments
+/// * `status` - `&str` - The `git status --porcelain` output
+/// * `diff` - `&str` - The diff of the changes
+/// * `rules` - `&BTreeMap<String, Vec<String>>` - The path globs per commit type
+///
+/// ## Returns
+/// * `Option<Suggestion>` - The suggestion, `None` if no rule matched
+pub fn suggest_commit_type(
+    status: &str,
+    diff: &str,
+    rules: &BTreeMap<String, Vec<String>>,
+) -> Option<Suggestion> {
+    let entries: Vec<(String, String)> = status.lines().filter_map(parse_status_line).collect();
+
+    if entries.is_empty() {
+        return None;
+    }
+
+    for (commit_type, globs) in rules {
+        let glob_set = build_glob_set(globs);
+
+        if entries.iter().all(|(_, path)| glob_set.is_match(path)) {
+            return Some(Suggestion {
+                commit_type: commit_type.clone(),
+                reason: format!(
+                    "all {} changed file(s) match the `{commit_type}` paths",
+                    entries.len()
+                ),
+            });
+        }
+    }
+
+    if let Some((_, path)) = entries
+        .iter()
+        .find(|(code, _)| code.starts_with('A') || code == "??")
+    {
+        return Some(Suggestion {
+            commit_type: "feat".to_string(),
+            reason: format!("new file `{path}`"),
+        });
+    }
+
+    find_new_public_item(diff).map(|name| Suggestion {
+        commit_type: "feat".to_string(),
+        reason: format!("new public item `{name}`"),
+    })
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::suggest_commit_type;
+    use crate::config::ClassifierConfig;
+
+    #[test]
+    fn test_suggest_commit_type() {
+        let rules = ClassifierConfig::default().rules;
+
+        let suggestion =
+            suggest_commit_type(" M tests/parser.rs\nA  src/lib_test.rs", "", &rules).unwrap();
+        assert_eq!(suggestion.commit_type, "test");
+
+        let suggestion =
+            suggest_commit_type(" M Cargo.toml\n M .github/workflows/ci.yml", "", &rules).unwrap();
+        assert_eq!(suggestion.commit_type, "chore");
+
+        let suggestion = suggest_commit_type(" M Cargo.toml\n?? src/new.rs", "", &rules).unwrap();
+        assert_eq!(suggestion.commit_type, "feat");
+        assert_eq!(suggestion.reason, "new file `src/new.rs`");
+
+        let diff = "+++ b/src/lib.rs\n+    pub fn parse(input: &str) {\n";
+        let suggestion = suggest_commit_type(" M src/lib.rs", diff, &rules).unwrap();
+        assert_eq!(suggestion.reason, "new public item `parse`");
+
+        assert_eq!(
+            suggest_commit_type(" M src/lib.rs", "+ let a = 1;", &rules),
+            None
+        );
+
+        // A modified or moved item is not new
+        let diff =
+            "+++ b/src/lib.rs\n-pub fn parse(input: &str) {\n+pub fn parse(input: &[u8]) {\n";
+        assert_eq!(suggest_commit_type(" M src/lib.rs", diff, &rules), None);
+    }
+}
@@ -0,0 +1,452 @@
+///
+/// # `config.rs`
+/// Contains the configuration read from the `.git-commands.toml` file.
+///
+/// Every section and every key is optional, missing values fall back to their defaults.
+// Imports ================================================================================= Imports
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use ansi_term::Colour::Yellow;
+use serde::Deserialize;
+
+// Structs ================================================================================= Structs
+/// The whole configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// `[release]` section
+    pub release: ReleaseConfig,
+    /// `[format]` section
+    pub format: FormatConfig,
+    /// `[classifier]` section
+    pub classifier: ClassifierConfig,
+    /// `[scopes]` section: path glob → scope.
+    /// Checked before the Cargo and npm workspace members.
+    pub scopes: BTreeMap<String, String>,
+    /// `[branch]` section
+    pub branch: BranchConfig,
+    /// `[pair]` section
+    pub pair: PairConfig,
+    /// `[commit]` section
+    pub commit: CommitConfig,
+    /// `[render]` section
+    pub render: RenderConfig,
+    /// `[push]` section
+    pub push: PushConfig,
+    /// `[[checks]]` sections, run before `commit` and `push`
+    pub checks: Vec<CheckConfig>,
+    /// `[secrets]` section
+    pub secrets: SecretsConfig,
+    /// `[guards]` section
+    pub guards: GuardsConfig,
+    /// `[draft]` section
+    pub draft: DraftConfig,
+}
+
+/// The `[release]` section.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ReleaseConfig {
+    /// Manifest files whose version is bumped, relative to the project root
+    pub manifests: Vec<String>,
+    /// Prefix of the release tags (`v` gives `v1.2.3`)
+    pub tag_prefix: String,
+    /// Release commit message.
+    /// `{commit_nb}`, `{branch}`, `{version}` and `{tag}` are replaced.
+    pub message: String,
+}
+
+impl Default for ReleaseConfig {
+    fn default() -> Self {
+        ReleaseConfig {
+            manifests: vec!["Cargo.toml".to_string()],
+            tag_prefix: "v".to_string(),
+            message: "[{commit_nb}] (chore on {branch}) Release {tag}".to_string(),
+        }
+    }
+}
+
+/// The `[format]` section.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct FormatConfig {
+    /// Regex the commit headers of the team are expected to match
+    pub header_pattern: String,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        FormatConfig {
+            header_pattern: r"^\[\d+\] \(\w+(\([^)]*\))?!? on [^)]+\) \S".to_string(),
+        }
+    }
+}
+
+/// The `[classifier]` section.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ClassifierConfig {
+    /// Path globs per commit type.
+    /// The type is suggested when every changed file matches one of its globs.
+    pub rules: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for ClassifierConfig {
+    fn default() -> Self {
+        let test = [
+            "tests/**",
+            "test/**",
+            "**/tests/**",
+            "**/*_test.*",
+            "**/*.test.*",
+            "**/*_spec.*",
+            "**/*.spec.*",
+        ];
+        let chore = [
+            "**/Cargo.toml",
+            "**/Cargo.lock",
+            "**/package.json",
+            "**/package-lock.json",
+            "**/yarn.lock",
+            "**/pnpm-lock.yaml",
+            "**/requirements*.txt",
+            "**/go.mod",
+            "**/go.sum",
+            ".github/**",
+            ".gitlab-ci.yml",
+            ".circleci/**",
+        ];
+
+        ClassifierConfig {
+            rules: BTreeMap::from([
+                ("test".to_string(), test.map(ToString::to_string).to_vec()),
+                ("chore".to_string(), chore.map(ToString::to_string).to_vec()),
+            ]),
+        }
+    }
+}
+
+/// The `[branch]` section.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct BranchConfig {
+    /// Regex parsing the branch names, with the `type`, `ticket` and `slug` named groups
+    pub pattern: String,
+    /// Trailer key used for the ticket (`Refs: PROJ-1234`)
+    pub ticket_trailer: String,
+    /// Branch name globs that require a ticket
+    pub require_ticket: Vec<String>,
+    /// The branch the feature branches start from, detected if not set
+    pub base: Option<String>,
+}
+
+impl Default for BranchConfig {
+    fn default() -> Self {
+        // The `type` group only takes the commit types, `tom/feat/x` has no type
+        BranchConfig {
+            pattern: r"^(?:(?P<type>chore|feat|fix|test)/)?(?:(?P<ticket>[A-Z][A-Z0-9]+-\d+)-?)?(?P<slug>.*)$"
+                .to_string(),
+            ticket_trailer: "Refs".to_string(),
+            require_ticket: Vec::new(),
+            base: None,
+        }
+    }
+}
+
+/// The `[pair]` section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PairConfig {
+    /// The team roster (`Name <email>`).
+    /// When empty, the `.mailmap` and the recent committers are used.
+    pub roster: Vec<String>,
+}
+
+/// The `[commit]` section, the defaults of the `commit` options.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CommitConfig {
+    /// Add a `Signed-off-by:` trailer
+    pub signoff: bool,
+    /// Sign the commits, with this key if not empty
+    pub gpg_sign: Option<String>,
+    /// Skip the `pre-commit` and `commit-msg` hooks
+    pub no_verify: bool,
+    /// Allow commits without changes
+    pub allow_empty: bool,
+    /// Override the commit author (`Name <email>`)
+    pub author: Option<String>,
+    /// Kill `git commit` (and its hooks) after this many seconds
+    pub timeout: Option<u64>,
+}
+
+/// The `[render]` section, how the draft is turned into the commit message.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct RenderConfig {
+    /// Drop the file bullets without description
+    pub drop_empty_bullets: bool,
+    /// Wrap the body lines at this width, `0` disables the wrapping
+    pub wrap_width: usize,
+    /// Remove the backticks and the bold / italic markers.
+    /// The `history` command can't read the messages rendered this way.
+    pub strip_markdown: bool,
+    /// Remove the lines starting with `#`
+    pub remove_comments: bool,
+    /// Collapse the consecutive blank lines
+    pub collapse_blank_lines: bool,
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        RenderConfig {
+            drop_empty_bullets: true,
+            wrap_width: 72,
+            strip_markdown: false,
+            remove_comments: true,
+            collapse_blank_lines: true,
+        }
+    }
+}
+
+/// The `[push]` section.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct PushConfig {
+    /// Remote on which the upstream of a new branch is set
+    pub remote: String,
+    /// Branch globs that need a confirmation before pushing
+    pub protected: Vec<String>,
+    /// Kill `git push` (and its hooks) after this many seconds
+    pub timeout: Option<u64>,
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        PushConfig {
+            remote: "origin".to_string(),
+            protected: vec!["main".to_string(), "master".to_string()],
+            timeout: None,
+        }
+    }
+}
+
+/// A `[[checks]]` section, a command run before `commit` or `push`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CheckConfig {
+    /// The name shown in the summary
+    pub name: String,
+    /// The shell command. `{files}` is replaced by the matching files,
+    /// `{packages}` by `-p <name>` for each Cargo package containing them.
+    pub command: String,
+    /// Globs of the files that trigger the check, any change if empty
+    pub paths: Vec<String>,
+    /// When the check runs: `commit`, `push` or both
+    pub on: Vec<String>,
+    /// Run alongside the other parallel checks
+    pub parallel: bool,
+}
+
+impl Default for CheckConfig {
+    fn default() -> Self {
+        CheckConfig {
+            name: String::new(),
+            command: String::new(),
+            paths: Vec::new(),
+            on: vec!["commit".to_string()],
+            parallel: true,
+        }
+    }
+}
+
+/// The `[secrets]` section, the scan of the staged changes before `commit`.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct SecretsConfig {
+    /// Scan the staged changes
+    pub enabled: bool,
+    /// File listing the allowed paths (globs) and values, relative to the project root
+    pub allowlist: String,
+    /// Minimum Shannon entropy of the values assigned to secret-like names
+    pub entropy: f64,
+    /// `[[secrets.rules]]` added to the built-in ones
+    pub rules: Vec<SecretRuleConfig>,
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        SecretsConfig {
+            enabled: true,
+            allowlist: ".secrets-allowlist".to_string(),
+            entropy: 4.0,
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// A `[[secrets.rules]]` section, matching added lines, file names or both.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SecretRuleConfig {
+    /// The name shown in the findings
+    pub name: String,
+    /// Regex matched against the added lines
+    pub pattern: Option<String>,
+    /// Glob matched against the staged file names
+    pub path: Option<String>,
+}
+
+/// What a guard does with its findings.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GuardLevel {
+    /// Print the findings
+    Warn,
+    /// Print the findings and stop the commit
+    Block,
+    /// Don't look
+    Off,
+}
+
+/// The `[guards]` section, the checks of the staged content before `commit`.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct GuardsConfig {
+    /// Size above which a staged file is reported, in kilobytes
+    pub max_size_kb: u64,
+    /// Files over `max_size_kb`
+    pub large_files: GuardLevel,
+    /// Leftover `<<<<<<<` and `>>>>>>>` markers
+    pub conflict_markers: GuardLevel,
+    /// Trailing whitespace and CRLF line endings on the added lines
+    pub whitespace: GuardLevel,
+    /// Newly added binary files
+    pub binaries: GuardLevel,
+    /// Executable bit changes
+    pub executable: GuardLevel,
+}
+
+impl Default for GuardsConfig {
+    fn default() -> Self {
+        GuardsConfig {
+            max_size_kb: 5 * 1024,
+            large_files: GuardLevel::Warn,
+            conflict_markers: GuardLevel::Block,
+            whitespace: GuardLevel::Warn,
+            binaries: GuardLevel::Warn,
+            executable: GuardLevel::Warn,
+        }
+    }
+}
+
+/// How the file bullets of a generated draft are grouped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Grouping {
+    /// One list of bullets
+    #[default]
+    Flat,
+    /// One group per top-level directory
+    Directory,
+    /// One group per workspace package
+    Crate,
+}
+
+/// The `[draft]` section, how the draft is generated.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DraftConfig {
+    /// Grouping of the file bullets, `--group` overrides it
+    pub grouping: Grouping,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `load_config`
+/// Loads the configuration file.
+/// If the file doesn't exist or can't be parsed, the default configuration is returned.
+///
+/// ## Arguments
+/// * `path` - `&Path` - The path to the configuration file
+///
+/// ## Returns
+/// * `Config` - The configuration
+pub fn load_config(path: &Path) -> Config {
+    if !path.exists() {
+        return Config::default();
+    }
+
+    let content = std::fs::read_to_string(path).unwrap_or_default();
+
+    match toml::from_str(&content) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!(
+                "{} {}: {e}",
+                Yellow.bold().paint("Ignoring invalid config"),
+                path.display()
+            );
+
+            Config::default()
+        }
+    }
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::{Config, Grouping, GuardLevel};
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config: Config = toml::from_str("[release]\ntag_prefix = \"\"\n").unwrap();
+
+        assert_eq!(config.release.tag_prefix, "");
+        assert_eq!(config.release.manifests, vec!["Cargo.toml"]);
+    }
+
+    #[test]
+    fn test_checks_config() {
+        let config: Config = toml::from_str(
+            "[[checks]]\nname = \"fmt\"\ncommand = \"cargo fmt --check\"\npaths = [\"**/*.rs\"]\n",
+        )
+        .unwrap();
+
+        assert_eq!(config.checks.len(), 1);
+        assert_eq!(config.checks[0].on, vec!["commit"]);
+        assert!(config.checks[0].parallel);
+    }
+
+    #[test]
+    fn test_secrets_config() {
+        let config: Config = toml::from_str(
+            "[secrets]\nentropy = 3.5\n\n[[secrets.rules]]\nname = \"Slack token\"\npattern = \"xox[bp]-[0-9A-Za-z-]+\"\n",
+        )
+        .unwrap();
+
+        assert!(config.secrets.enabled);
+        assert_eq!(config.secrets.rules[0].name, "Slack token");
+        assert_eq!(config.secrets.rules[0].path, None);
+    }
+
+    #[test]
+    fn test_guards_config() {
+        let config: Config =
+            toml::from_str("[guards]\nwhitespace = \"off\"\nbinaries = \"block\"\n").unwrap();
+
+        assert_eq!(config.guards.whitespace, GuardLevel::Off);
+        assert_eq!(config.guards.binaries, GuardLevel::Block);
+        assert_eq!(config.guards.conflict_markers, GuardLevel::Block);
+    }
+
+    #[test]
+    fn test_draft_config() {
+        let config: Config = toml::from_str("[draft]\ngrouping = \"crate\"\n").unwrap();
+
+        assert_eq!(config.draft.grouping, Grouping::Crate);
+        assert!(toml::from_str::<Config>("[draft]\ngrouping = \"tree\"\n").is_err());
+        assert_eq!(Config::default().draft.grouping, Grouping::Flat);
+    }
+}
@@ -0,0 +1,354 @@
+///
+/// # `diffstat.rs`
+/// Summarizes the changes of each file for the generated draft: line counts,
+/// new, renamed, deleted, mode-changed, binary and submodule entries.
+///
+/// The staged and the unstaged counts are told apart, the summaries are written
+/// as `#` comments so they never reach the commit message.
+// Imports ================================================================================= Imports
+use std::collections::BTreeMap;
+
+use crate::draft::annotate_bullets;
+use crate::git_related::read_diff_summary;
+
+// Constants  ===========================================================================  Constants
+/// Mode of the submodule entries
+const SUBMODULE_MODE: &str = "160000";
+/// Mode of the missing side of an added or deleted entry
+const MISSING_MODE: &str = "000000";
+
+// Structs  ===============================================================================  Structs
+/// An entry of `git diff --raw`.
+#[derive(Debug, PartialEq)]
+pub struct RawEntry {
+    /// The status letter (`A`, `M`, `D`, `R`, `T`, ...)
+    pub status: char,
+    /// The mode before the change
+    pub old_mode: String,
+    /// The mode after the change
+    pub new_mode: String,
+    /// The path, the new one for a rename
+    pub path: String,
+    /// The path before a rename or a copy
+    pub old_path: Option<String>,
+}
+
+/// The added and removed lines of a file, `None` for a binary file.
+pub type LineCounts = Option<(usize, usize)>;
+
+/// The changes of a staged file.
+#[derive(Debug, PartialEq)]
+pub struct FileStat {
+    /// The staged entry
+    pub entry: RawEntry,
+    /// The staged line counts
+    pub staged: LineCounts,
+    /// The line counts not staged yet, `None` if the file has no unstaged change
+    pub unstaged: Option<LineCounts>,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `parse_raw`
+/// Parses the output of `git diff --raw -z`.
+///
+/// ## Arguments
+/// * `output` - `&str` - The raw output
+///
+/// ## Returns
+/// * `Vec<RawEntry>` - The entries
+pub fn parse_raw(output: &str) -> Vec<RawEntry> {
+    let mut entries: Vec<RawEntry> = Vec::new();
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+
+    // `:100644 100755 1a2b 3c4d R087` then the path, or the old and new paths
+    while let Some(metadata) = fields.next() {
+        let mut metadata = metadata.trim_start_matches(':').split(' ');
+        let (Some(old_mode), Some(new_mode), Some(status)) =
+            (metadata.next(), metadata.next(), metadata.nth(2))
+        else {
+            break;
+        };
+        let status = status.chars().next().unwrap_or('M');
+
+        let first_path = fields.next().unwrap_or_default().to_string();
+        let (path, old_path) = if matches!(status, 'R' | 'C') {
+            (
+                fields.next().unwrap_or_default().to_string(),
+                Some(first_path),
+            )
+        } else {
+            (first_path, None)
+        };
+
+        entries.push(RawEntry {
+            status,
+            old_mode: old_mode.to_string(),
+            new_mode: new_mode.to_string(),
+            path,
+            old_path,
+        });
+    }
+
+    entries
+}
+
+///
+/// # `parse_numstat`
+/// Parses the output of `git diff --numstat -z`.
+///
+/// ## Arguments
+/// * `output` - `&str` - The numstat output
+///
+/// ## Returns
+/// * `BTreeMap<String, LineCounts>` - The line counts by path, the new one for a rename
+pub fn parse_numstat(output: &str) -> BTreeMap<String, LineCounts> {
+    let mut counts: BTreeMap<String, LineCounts> = BTreeMap::new();
+    let mut fields = output.split('\0');
+
+    // `12\t3\tpath`, or `12\t3\t` followed by the old and the new paths
+    while let Some(field) = fields.next() {
+        let mut parts = field.splitn(3, '\t');
+        let (Some(added), Some(removed), Some(path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+
+        let path = if path.is_empty() {
+            fields.nth(1).unwrap_or_default()
+        } else {
+            path
+        };
+
+        counts.insert(
+            path.to_string(),
+            added.parse().ok().zip(removed.parse().ok()),
+        );
+    }
+
+    counts
+}
+
+///
+/// # `build_stats`
+/// Joins the staged entries with their staged and unstaged line counts.
+///
+/// ## Arguments
+/// * `entries` - `Vec<RawEntry>` - The staged entries
+/// * `staged` - `&BTreeMap<String, LineCounts>` - The staged line counts
+/// * `unstaged` - `&BTreeMap<String, LineCounts>` - The unstaged line counts
+///
+/// ## Returns
+/// * `Vec<FileStat>` - The file changes
+pub fn build_stats(
+    entries: Vec<RawEntry>,
+    staged: &BTreeMap<String, LineCounts>,
+    unstaged: &BTreeMap<String, LineCounts>,
+) -> Vec<FileStat> {
+    entries
+        .into_iter()
+        .map(|entry| FileStat {
+            staged: staged.get(&entry.path).copied().flatten(),
+            unstaged: unstaged.get(&entry.path).copied(),
+            entry,
+        })
+        .collect()
+}
+
+///
+/// # `format_counts`
+/// Formats line counts.
+///
+/// ## Arguments
+/// * `counts` - `LineCounts` - The line counts
+///
+/// ## Returns
+/// * `String` - `+12 -3`, or `binary`
+fn format_counts(counts: LineCounts) -> String {
+    match counts {
+        Some((added, removed)) => format!("+{added} -{removed}"),
+        None => "binary".to_string(),
+    }
+}
+
+///
+/// # `describe_stat`
+/// Describes the changes of a file on one comment line.
+///
+/// ## Arguments
+/// * `stat` - `&FileStat` - The file changes
+///
+/// ## Returns
+/// * `String` - The description (`# staged +12 -3 · new file · not staged +1 -0`)
+pub fn describe_stat(stat: &FileStat) -> String {
+    let entry = &stat.entry;
+    let mut parts: Vec<String> = Vec::new();
+
+    if entry.old_mode == SUBMODULE_MODE || entry.new_mode == SUBMODULE_MODE {
+        parts.push("staged submodule update".to_string());
+    } else {
+        parts.push(format!("staged {}", format_counts(stat.staged)));
+    }
+
+    match entry.status {
+        'A' => parts.push("new file".to_string()),
+        'D' => parts.push("deleted".to_string()),
+        'R' | 'C' => parts.push(format!(
+            "{} `{}` → `{}`",
+            if entry.status == 'R' {
+                "renamed"
+            } else {
+                "copied"
+            },
+            entry.old_path.as_deref().unwrap_or_default(),
+            entry.path
+        )),
+        _ => {}
+    }
+
+    if entry.old_mode != entry.new_mode
+        && entry.old_mode != MISSING_MODE
+        && entry.new_mode != MISSING_MODE
+    {
+        parts.push(format!("mode {} → {}", entry.old_mode, entry.new_mode));
+    }
+
+    if let Some(unstaged) = stat.unstaged {
+        parts.push(format!("not staged {}", format_counts(unstaged)));
+    }
+
+    format!("# {}", parts.join(" · "))
+}
+
+///
+/// # `summary_line`
+/// Sums the staged and the unstaged changes on one comment line.
+///
+/// ## Arguments
+/// * `stats` - `&[FileStat]` - The staged file changes
+/// * `unstaged` - `&BTreeMap<String, LineCounts>` - The unstaged line counts of every file
+///
+/// ## Returns
+/// * `String` - The summary (`# 3 file(s) staged +20 -4, 1 file(s) not staged +2 -0`)
+pub fn summary_line(stats: &[FileStat], unstaged: &BTreeMap<String, LineCounts>) -> String {
+    let sum = |counts: &mut dyn Iterator<Item = LineCounts>| {
+        counts
+            .flatten()
+            .fold((0, 0), |(added, removed), (file_added, file_removed)| {
+                (added + file_added, removed + file_removed)
+            })
+    };
+
+    let (added, removed) = sum(&mut stats.iter().map(|stat| stat.staged));
+    let mut summary = format!("# {} file(s) staged +{added} -{removed}", stats.len());
+
+    if !unstaged.is_empty() {
+        let (added, removed) = sum(&mut unstaged.values().copied());
+        summary.push_str(&format!(
+            ", {} file(s) not staged +{added} -{removed}",
+            unstaged.len()
+        ));
+    }
+
+    summary
+}
+
+///
+/// # `annotate_stats`
+/// Writes the summary under the header and the changes of each file under its bullet.
+///
+/// ## Arguments
+/// * `draft` - `&str` - The draft
+/// * `stats` - `&[FileStat]` - The staged file changes
+/// * `unstaged` - `&BTreeMap<String, LineCounts>` - The unstaged line counts of every file
+///
+/// ## Returns
+/// * `String` - The annotated draft
+pub fn annotate_stats(
+    draft: &str,
+    stats: &[FileStat],
+    unstaged: &BTreeMap<String, LineCounts>,
+) -> String {
+    if stats.is_empty() {
+        return draft.to_string();
+    }
+
+    let notes: BTreeMap<String, Vec<String>> = stats
+        .iter()
+        .map(|stat| (stat.entry.path.clone(), vec![describe_stat(stat)]))
+        .collect();
+
+    let annotated = annotate_bullets(draft, &notes);
+    let (header, body) = annotated.split_once('\n').unwrap_or((&annotated, ""));
+
+    format!("{header}\n{}\n{body}", summary_line(stats, unstaged))
+}
+
+///
+/// # `annotate_staged_stats`
+/// Annotates a draft with the staged and the unstaged changes of the working tree.
+///
+/// ## Arguments
+/// * `draft` - `&str` - The draft
+///
+/// ## Returns
+/// * `String` - The annotated draft
+pub fn annotate_staged_stats(draft: &str) -> String {
+    let staged = parse_numstat(&read_diff_summary(&["--cached", "--numstat", "-z", "-M"]));
+    let unstaged = parse_numstat(&read_diff_summary(&["--numstat", "-z"]));
+    let entries = parse_raw(&read_diff_summary(&["--cached", "--raw", "-z", "-M"]));
+
+    annotate_stats(draft, &build_stats(entries, &staged, &unstaged), &unstaged)
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::{annotate_stats, build_stats, parse_numstat, parse_raw};
+
+    const RAW: &str = ":100644 100755 1a2b3c4 5d6e7f8 M\0build.sh\0\
+        :000000 100644 0000000 1a2b3c4 A\0src/new.rs\0\
+        :100644 100644 1a2b3c4 1a2b3c4 R100\0src/old.rs\0src/moved.rs\0\
+        :000000 100644 0000000 9f8e7d6 A\0logo.png\0\
+        :160000 160000 1a2b3c4 5d6e7f8 M\0vendor/lib\0";
+    const NUMSTAT: &str =
+        "1\t1\tbuild.sh\x0012\t0\tsrc/new.rs\x000\t0\t\0src/old.rs\0src/moved.rs\0-\t-\tlogo.png\x001\t1\tvendor/lib\0";
+
+    #[test]
+    fn test_parse_raw() {
+        let entries = parse_raw(RAW);
+
+        assert_eq!(entries.len(), 5);
+        assert_eq!(entries[2].status, 'R');
+        assert_eq!(entries[2].path, "src/moved.rs");
+        assert_eq!(entries[2].old_path.as_deref(), Some("src/old.rs"));
+    }
+
+    #[test]
+    fn test_parse_numstat() {
+        let counts = parse_numstat(NUMSTAT);
+
+        assert_eq!(counts["src/new.rs"], Some((12, 0)));
+        assert_eq!(counts["src/moved.rs"], Some((0, 0)));
+        assert_eq!(counts["logo.png"], None);
+    }
+
+    #[test]
+    fn test_annotate_stats() {
+        let unstaged = parse_numstat("2\t0\tsrc/new.rs\0");
+        let stats = build_stats(parse_raw(RAW), &parse_numstat(NUMSTAT), &unstaged);
+        let draft = "[3] (feat on login)\n\n- `build.sh`:\n- `src/new.rs`:\n- `src/old.rs -> src/moved.rs`:\n- `logo.png`:\n- `vendor/lib`:\n";
+
+        assert_eq!(
+            annotate_stats(draft, &stats, &unstaged),
+            "[3] (feat on login)\n\
+            # 5 file(s) staged +14 -2, 1 file(s) not staged +2 -0\n\
+            \n\
+            - `build.sh`:\n# staged +1 -1 · mode 100644 → 100755\n\
+            - `src/new.rs`:\n# staged +12 -0 · new file · not staged +2 -0\n\
+            - `src/old.rs -> src/moved.rs`:\n# staged +0 -0 · renamed `src/old.rs` → `src/moved.rs`\n\
+            - `logo.png`:\n# staged binary · new file\n\
+            - `vendor/lib`:\n# staged submodule update\n"
+        );
+    }
+}
@@ -0,0 +1,480 @@
+///
+/// # `draft.rs`
+/// Parses the messages written with the `commit_message.md` format.
+///
+/// The first line is the header: `[commit_nb] (type on branch) Summary`.
+/// Conventional commits headers (`type(scope)!: Summary`) are understood as well.
+// Imports ================================================================================= Imports
+use std::collections::BTreeMap;
+
+use regex::Regex;
+
+// Constants  ===========================================================================  Constants
+/// A trailer line: `Key: value`, not indented
+pub const TRAILER_PATTERN: &str = r"^[A-Z][A-Za-z-]*: \S";
+
+// Structs ================================================================================= Structs
+/// A parsed commit message header.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Header {
+    /// The commit number, only set for the headers written by this tool
+    pub commit_nb: Option<u32>,
+    /// The commit type (`feat`, `fix`, ...)
+    pub commit_type: String,
+    /// The optional scope
+    pub scope: Option<String>,
+    /// Is the commit marked as breaking with a `!` ?
+    pub breaking: bool,
+    /// The branch name, only set for the headers written by this tool
+    pub branch: Option<String>,
+    /// The text following the header
+    pub summary: String,
+}
+
+/// A file bullet of a message, with its description.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FileDescription {
+    /// The path written between backticks
+    pub path: String,
+    /// The description text, empty if the bullet was left blank
+    pub description: String,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `parse_header`
+/// Parses the first line of a commit message.
+///
+/// ## Arguments
+/// * `line` - `&str` - The header line
+///
+/// ## Example
+/// ```rust
+/// let header = parse_header("[12] (feat on login) Add the form").unwrap();
+///
+/// assert_eq!(header.commit_type, "feat");
+/// assert_eq!(header.branch, Some("login".to_string()));
+/// ```
+///
+/// ## Returns
+/// * `Option<Header>` - The header, `None` if the line doesn't follow any known format
+pub fn parse_header(line: &str) -> Option<Header> {
+    let tool_rule =
+        Regex::new(r"^\[(\d+)\]\s*\((\w+)(?:\(([^)]*)\))?(!)?\s+on\s+([^)]*)\)\s*(.*)$").unwrap();
+    let conventional_rule = Regex::new(r"^(\w+)(?:\(([^)]*)\))?(!)?:\s*(.*)$").unwrap();
+
+    let line = line.trim();
+
+    if let Some(captures) = tool_rule.captures(line) {
+        return Some(Header {
+            commit_nb: captures[1].parse().ok(),
+            commit_type: captures[2].to_string(),
+            scope: captures.get(3).map(|scope| scope.as_str().to_string()),
+            breaking: captures.get(4).is_some(),
+            branch: Some(captures[5].trim().to_string()),
+            summary: captures[6].trim().to_string(),
+        });
+    }
+
+    conventional_rule.captures(line).map(|captures| Header {
+        commit_nb: None,
+        commit_type: captures[1].to_string(),
+        scope: captures.get(2).map(|scope| scope.as_str().to_string()),
+        breaking: captures.get(3).is_some(),
+        branch: None,
+        summary: captures[4].trim().to_string(),
+    })
+}
+
+///
+/// # `trailer_block_start`
+/// Finds the trailers of a message: the last paragraph, when all its lines are trailers.
+/// A trailer-like line elsewhere (`Note: see below`) is part of the text.
+///
+/// ## Arguments
+/// * `lines` - `&[&str]` - The lines of the message
+///
+/// ## Returns
+/// * `usize` - The index of the first trailer, the number of lines if there is none
+pub fn trailer_block_start(lines: &[&str]) -> usize {
+    let trailer_rule = Regex::new(TRAILER_PATTERN).unwrap();
+
+    let end = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(0, |index| index + 1);
+    let Some(blank) = lines[..end].iter().rposition(|line| line.trim().is_empty()) else {
+        // The header paragraph has no trailers
+        return lines.len();
+    };
+
+    if lines[blank + 1..end]
+        .iter()
+        .all(|line| trailer_rule.is_match(line))
+    {
+        blank + 1
+    } else {
+        lines.len()
+    }
+}
+
+///
+/// # `parse_file_descriptions`
+/// Parses the file bullets of a message.
+/// A bullet is a `` - `path`: `` line, its description is the text following
+/// the colon and the lines below it, up to the next bullet or the trailers.
+///
+/// ## Arguments
+/// * `message` - `&str` - The commit message
+///
+/// ## Returns
+/// * `Vec<FileDescription>` - The described files, in the message order
+pub fn parse_file_descriptions(message: &str) -> Vec<FileDescription> {
+    let bullet_rule = Regex::new(r"^- `([^`]+)`:(.*)$").unwrap();
+
+    let message_lines: Vec<&str> = message.lines().collect();
+    let trailers_start = trailer_block_start(&message_lines);
+
+    let mut descriptions: Vec<FileDescription> = Vec::new();
+    let mut lines: Vec<&str> = Vec::new();
+
+    for line in &message_lines[..trailers_start] {
+        let line = *line;
+
+        if let Some(captures) = bullet_rule.captures(line) {
+            if let Some(last) = descriptions.last_mut() {
+                last.description = join_description(&lines);
+            }
+
+            lines = vec![captures.get(2).unwrap().as_str()];
+            descriptions.push(FileDescription {
+                path: captures[1].to_string(),
+                description: String::new(),
+            });
+        } else if !descriptions.is_empty() {
+            lines.push(line);
+        }
+    }
+
+    if let Some(last) = descriptions.last_mut() {
+        last.description = join_description(&lines);
+    }
+
+    descriptions
+}
+
+///
+/// # `parse_trailers`
+/// Parses the trailers (`Refs: PROJ-12`, `Co-authored-by: ...`) of a message.
+///
+/// ## Arguments
+/// * `message` - `&str` - The commit message
+///
+/// ## Returns
+/// * `Vec<(String, String)>` - The trailers keys and values
+pub fn parse_trailers(message: &str) -> Vec<(String, String)> {
+    let lines: Vec<&str> = message.lines().collect();
+
+    lines[trailer_block_start(&lines)..]
+        .iter()
+        .filter_map(|line| line.split_once(": "))
+        .map(|(key, value)| (key.to_string(), value.trim().to_string()))
+        .collect()
+}
+
+///
+/// # `append_trailers`
+/// Appends trailers to a message, skipping the ones it already has.
+///
+/// ## Arguments
+/// * `message` - `&str` - The commit message
+/// * `trailers` - `&[String]` - The trailers (`Key: value`)
+///
+/// ## Returns
+/// * `String` - The message with the trailers
+pub fn append_trailers(message: &str, trailers: &[String]) -> String {
+    let trailer_rule = Regex::new(TRAILER_PATTERN).unwrap();
+
+    let missing: Vec<&String> = trailers
+        .iter()
+        .filter(|trailer| !message.lines().any(|line| line.trim() == trailer.as_str()))
+        .collect();
+
+    if missing.is_empty() {
+        return message.to_string();
+    }
+
+    let mut new_message = message.trim_end().to_string();
+
+    // Trailers must be in the last paragraph
+    let ends_with_trailer = new_message
+        .lines()
+        .next_back()
+        .is_some_and(|line| trailer_rule.is_match(line));
+    new_message.push_str(if ends_with_trailer { "\n" } else { "\n\n" });
+
+    for trailer in missing {
+        new_message.push_str(trailer);
+        new_message.push('\n');
+    }
+
+    new_message
+}
+
+///
+/// # `build_amend_draft`
+/// Builds the draft used to amend a commit: the header, the text before the bullets
+/// and the trailers of the commit message are kept, every file gets a bullet with
+/// its previous description, if any.
+///
+/// ## Arguments
+/// * `message` - `&str` - The message of the amended commit
+/// * `files` - `&[String]` - The files of the amended commit and the new changes
+///
+/// ## Returns
+/// * `String` - The draft
+pub fn build_amend_draft(message: &str, files: &[String]) -> String {
+    let bullet_rule = Regex::new(r"^- `([^`]+)`:").unwrap();
+
+    let descriptions = parse_file_descriptions(message);
+    let trailers = parse_trailers(message);
+
+    // The header and the text before the first bullet
+    let message_lines: Vec<&str> = message.lines().collect();
+    let trailers_start = trailer_block_start(&message_lines);
+    let mut lines = message_lines[..trailers_start].iter().copied();
+    let mut preamble: Vec<&str> = lines.next().into_iter().collect();
+    preamble.extend(lines.take_while(|line| !bullet_rule.is_match(line)));
+
+    let mut draft = format!("{}\n\n", preamble.join("\n").trim_end());
+
+    for file in files {
+        let description = descriptions
+            .iter()
+            .find(|described| described.path == *file)
+            .map_or("", |described| described.description.as_str());
+
+        draft.push_str(&format!("- `{file}`:\n\n\t{description}\n\n"));
+    }
+
+    for (key, value) in trailers {
+        draft.push_str(&format!("{key}: {value}\n"));
+    }
+
+    draft
+}
+
+///
+/// # `annotate_bullets`
+/// Inserts lines under the bullets of their files.
+/// A renamed file bullet (`` - `old -> new`: ``) gets the lines of the new path.
+///
+/// ## Arguments
+/// * `draft` - `&str` - The draft
+/// * `notes` - `&BTreeMap<String, Vec<String>>` - The lines to insert, by file
+///
+/// ## Returns
+/// * `String` - The annotated draft
+pub fn annotate_bullets(draft: &str, notes: &BTreeMap<String, Vec<String>>) -> String {
+    let bullet_rule = Regex::new(r"^- `([^`]+)`:").unwrap();
+    let mut annotated = String::new();
+
+    for line in draft.lines() {
+        annotated.push_str(line);
+        annotated.push('\n');
+
+        let Some(captures) = bullet_rule.captures(line) else {
+            continue;
+        };
+
+        let path = captures[1].rsplit(" -> ").next().unwrap_or_default();
+        for note in notes.get(path).into_iter().flatten() {
+            annotated.push_str(note);
+            annotated.push('\n');
+        }
+    }
+
+    annotated
+}
+
+///
+/// # `is_group_heading`
+/// Checks if a bullet is a group heading (`` - `src/`: ``): a directory whose
+/// description covers every file under it.
+///
+/// ## Arguments
+/// * `path` - `&str` - The path written between backticks
+///
+/// ## Returns
+/// * `bool` - `true` for a directory path
+pub fn is_group_heading(path: &str) -> bool {
+    path.ends_with('/')
+}
+
+///
+/// # `undescribed_files`
+/// Returns the file bullets left blank and not covered by a described group heading.
+/// The `#` comment lines are ignored.
+///
+/// ## Arguments
+/// * `draft` - `&str` - The draft
+///
+/// ## Returns
+/// * `Vec<String>` - The undescribed files, in the draft order
+pub fn undescribed_files(draft: &str) -> Vec<String> {
+    let without_comments: Vec<&str> = draft
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect();
+    let descriptions = parse_file_descriptions(&without_comments.join("\n"));
+
+    let described_groups: Vec<&str> = descriptions
+        .iter()
+        .filter(|described| is_group_heading(&described.path) && !described.description.is_empty())
+        .map(|described| described.path.as_str())
+        .collect();
+
+    descriptions
+        .iter()
+        .filter(|described| !is_group_heading(&described.path) && described.description.is_empty())
+        .filter(|described| {
+            let path = described.path.rsplit(" -> ").next().unwrap_or_default();
+            !described_groups.iter().any(|group| path.starts_with(group))
+        })
+        .map(|described| described.path.clone())
+        .collect()
+}
+
+///
+/// # `join_description`
+/// Joins the trimmed non-empty lines of a description with spaces.
+///
+/// ## Arguments
+/// * `lines` - `&[&str]` - The description lines
+///
+/// ## Returns
+/// * `String` - The description
+fn join_description(lines: &[&str]) -> String {
+    lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{
+        annotate_bullets, append_trailers, build_amend_draft, parse_file_descriptions,
+        parse_header, parse_trailers, undescribed_files,
+    };
+
+    #[test]
+    fn test_parse_header() {
+        let header = parse_header("[58] (feat on release) Add the release command").unwrap();
+
+        assert_eq!(header.commit_nb, Some(58));
+        assert_eq!(header.commit_type, "feat");
+        assert_eq!(header.branch, Some("release".to_string()));
+        assert_eq!(header.summary, "Add the release command");
+        assert!(!header.breaking);
+
+        let header = parse_header("fix(api)!: Drop the v1 routes").unwrap();
+
+        assert_eq!(header.commit_nb, None);
+        assert_eq!(header.commit_type, "fix");
+        assert_eq!(header.scope, Some("api".to_string()));
+        assert!(header.breaking);
+
+        assert_eq!(parse_header("Merge branch 'main'"), None);
+    }
+
+    #[test]
+    fn test_parse_file_descriptions() {
+        let message = "[3] (feat on main) Add\n\n\n- `src/main.rs`:\n\n\tAdd the history\n\tcommand.\n\n- `src/utils.rs`:\n\n\t\n\n- `old.rs`: deleted\n";
+
+        let descriptions = parse_file_descriptions(message);
+
+        assert_eq!(descriptions.len(), 3);
+        assert_eq!(descriptions[0].path, "src/main.rs");
+        assert_eq!(descriptions[0].description, "Add the history command.");
+        assert_eq!(descriptions[1].description, "");
+        assert_eq!(descriptions[2].description, "deleted");
+    }
+
+    #[test]
+    fn test_parse_trailers() {
+        let message =
+            "[3] (feat on login) Add\n\n- `src/main.rs`:\n\n\tAdd: the form\n\nRefs: PROJ-12\n";
+
+        assert_eq!(
+            parse_trailers(message),
+            vec![("Refs".to_string(), "PROJ-12".to_string())]
+        );
+        assert_eq!(
+            parse_file_descriptions(message)[0].description,
+            "Add: the form"
+        );
+
+        // Only the last paragraph holds trailers
+        let message = "[3] (feat on login) Add\n\n- `a.rs`:\nNote: see below\n\n- `b.rs`: Done\n";
+
+        assert!(parse_trailers(message).is_empty());
+        assert_eq!(parse_file_descriptions(message).len(), 2);
+        assert_eq!(
+            parse_file_descriptions(message)[0].description,
+            "Note: see below"
+        );
+    }
+
+    #[test]
+    fn test_append_trailers() {
+        let trailers = vec!["Co-authored-by: Ana <ana@mail.com>".to_string()];
+
+        assert_eq!(
+            append_trailers("[3] (feat on login) Add\n\n- `a.rs`:\n\n\tDone\n", &trailers),
+            "[3] (feat on login) Add\n\n- `a.rs`:\n\n\tDone\n\nCo-authored-by: Ana <ana@mail.com>\n"
+        );
+        assert_eq!(
+            append_trailers("[3] (feat on login) Add\n\nRefs: PROJ-12\n", &trailers),
+            "[3] (feat on login) Add\n\nRefs: PROJ-12\nCo-authored-by: Ana <ana@mail.com>\n"
+        );
+        assert_eq!(
+            append_trailers("Add\n\nCo-authored-by: Ana <ana@mail.com>\n", &trailers),
+            "Add\n\nCo-authored-by: Ana <ana@mail.com>\n"
+        );
+    }
+
+    #[test]
+    fn test_build_amend_draft() {
+        let message = "[3] (feat on login) Add the form\n\n- `src/form.rs`:\n\n\tAdd the form.\n\nRefs: PROJ-12";
+        let files = vec!["src/form.rs".to_string(), "src/style.css".to_string()];
+
+        assert_eq!(
+            build_amend_draft(message, &files),
+            "[3] (feat on login) Add the form\n\n- `src/form.rs`:\n\n\tAdd the form.\n\n- `src/style.css`:\n\n\t\n\nRefs: PROJ-12\n"
+        );
+    }
+
+    #[test]
+    fn test_annotate_bullets() {
+        let draft = "[3] (fix on login)\n\n- `a.rs -> b.rs`:\n\n\t\n\n- `c.rs`:\n";
+        let notes = BTreeMap::from([("b.rs".to_string(), vec!["# renamed".to_string()])]);
+
+        assert_eq!(
+            annotate_bullets(draft, &notes),
+            "[3] (fix on login)\n\n- `a.rs -> b.rs`:\n# renamed\n\n\t\n\n- `c.rs`:\n"
+        );
+    }
+
+    #[test]
+    fn test_undescribed_files() {
+        let draft = "[3] (feat on login)\n\n- `README.md`:\n# staged +1 -0\n\n\t\n\n- `src/`:\n\n\tAdd the login form.\n\n- `src/form.rs`:\n\n\t\n\n- `tests/`:\n\n\t\n\n- `tests/form.rs`:\n\n\t\n";
+
+        assert_eq!(undescribed_files(draft), vec!["README.md", "tests/form.rs"]);
+    }
+}
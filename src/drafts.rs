@@ -0,0 +1,309 @@
+///
+/// # `drafts.rs`
+/// Keeps one draft per branch.
+///
+/// `commit_message.md` holds the draft of the branch it was written on. When another
+/// branch is checked out, the draft is put aside under `.git/git-commands/drafts/<branch>.md`
+/// and the draft of the new branch, if any, takes its place.
+// Imports ================================================================================= Imports
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Constants  ===========================================================================  Constants
+/// Folder of the drafts put aside, in the git directory
+const DRAFTS_DIR: &str = "git-commands/drafts";
+/// File holding the branch `commit_message.md` belongs to
+const OWNER_FILE: &str = ".branch";
+/// Extension of the drafts put aside
+const DRAFT_EXTENSION: &str = "md";
+
+// Structs  ===============================================================================  Structs
+/// What happened to the drafts when the branch changed.
+#[derive(Debug, PartialEq)]
+pub struct DraftSwap {
+    /// The branch whose draft was put aside
+    pub previous: String,
+    /// Was a draft of the current branch brought back ?
+    pub restored: bool,
+    /// The dated name an older draft of `previous` was moved to, instead of being overwritten
+    pub kept: Option<String>,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `drafts_dir`
+/// Returns the folder of the drafts put aside.
+///
+/// ## Arguments
+/// * `git_dir` - `&Path` - The git directory
+///
+/// ## Returns
+/// * `PathBuf` - The folder
+pub fn drafts_dir(git_dir: &Path) -> PathBuf {
+    git_dir.join(DRAFTS_DIR)
+}
+
+///
+/// # `stored_draft_path`
+/// Returns where the draft of a branch is put aside.
+///
+/// ## Arguments
+/// * `drafts_dir` - `&Path` - The folder of the drafts
+/// * `branch` - `&str` - The branch
+///
+/// ## Returns
+/// * `PathBuf` - The path (`feat/login` → `feat/login.md`)
+pub fn stored_draft_path(drafts_dir: &Path, branch: &str) -> PathBuf {
+    drafts_dir.join(format!("{branch}.{DRAFT_EXTENSION}"))
+}
+
+///
+/// # `has_content`
+/// Checks if a draft holds something else than blank lines.
+///
+/// ## Arguments
+/// * `path` - `&Path` - The draft
+///
+/// ## Returns
+/// * `bool` - `false` for a blank or missing draft
+fn has_content(path: &Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|content| !content.trim().is_empty())
+}
+
+///
+/// # `move_file`
+/// Moves a file, the missing parent folders are created.
+/// The drafts folder and the project may be on different file systems, so the file is copied.
+///
+/// ## Arguments
+/// * `from` - `&Path` - The file
+/// * `to` - `&Path` - The destination
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the move
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    std::fs::copy(from, to).map_err(|e| e.to_string())?;
+    std::fs::remove_file(from).map_err(|e| e.to_string())
+}
+
+///
+/// # `read_owner`
+/// Returns the branch `commit_message.md` belongs to.
+///
+/// ## Arguments
+/// * `drafts_dir` - `&Path` - The folder of the drafts
+///
+/// ## Returns
+/// * `Option<String>` - The branch, `None` before the first synchronization
+pub fn read_owner(drafts_dir: &Path) -> Option<String> {
+    std::fs::read_to_string(drafts_dir.join(OWNER_FILE))
+        .ok()
+        .map(|owner| owner.trim().to_string())
+        .filter(|owner| !owner.is_empty())
+}
+
+///
+/// # `write_owner`
+/// Records the branch `commit_message.md` belongs to.
+///
+/// ## Arguments
+/// * `drafts_dir` - `&Path` - The folder of the drafts
+/// * `branch` - `&str` - The branch
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the write
+fn write_owner(drafts_dir: &Path, branch: &str) -> Result<(), String> {
+    std::fs::create_dir_all(drafts_dir).map_err(|e| e.to_string())?;
+    std::fs::write(drafts_dir.join(OWNER_FILE), format!("{branch}\n")).map_err(|e| e.to_string())
+}
+
+///
+/// # `sync_draft`
+/// Puts the draft of the previous branch aside and brings back the draft of
+/// the current one. Nothing happens while the branch stays the same.
+///
+/// ## Arguments
+/// * `drafts_dir` - `&Path` - The folder of the drafts
+/// * `draft_path` - `&Path` - The `commit_message.md` file
+/// * `branch` - `&str` - The current branch
+///
+/// ## Returns
+/// * `Result<Option<DraftSwap>, String>` - The swap, `None` if the branch didn't change
+pub fn sync_draft(
+    drafts_dir: &Path,
+    draft_path: &Path,
+    branch: &str,
+) -> Result<Option<DraftSwap>, String> {
+    let previous = match read_owner(drafts_dir) {
+        Some(owner) if owner == branch => return Ok(None),
+        Some(owner) => owner,
+        None => return write_owner(drafts_dir, branch).map(|()| None),
+    };
+
+    // A blank draft is not kept
+    let previous_path = stored_draft_path(drafts_dir, &previous);
+    let mut kept: Option<String> = None;
+    if has_content(draft_path) {
+        // A draft already put aside (interrupted sync, re-created branch) gets a dated name
+        if previous_path.exists() {
+            let seconds = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs());
+            let dated = format!("{previous}.{seconds}");
+
+            move_file(&previous_path, &stored_draft_path(drafts_dir, &dated))?;
+            kept = Some(dated);
+        }
+
+        move_file(draft_path, &previous_path)?;
+    } else if draft_path.exists() {
+        std::fs::remove_file(draft_path).map_err(|e| e.to_string())?;
+    }
+
+    let current_path = stored_draft_path(drafts_dir, branch);
+    let restored = current_path.exists();
+    if restored {
+        move_file(&current_path, draft_path)?;
+    }
+
+    write_owner(drafts_dir, branch)?;
+
+    Ok(Some(DraftSwap {
+        previous,
+        restored,
+        kept,
+    }))
+}
+
+///
+/// # `list_stored_drafts`
+/// Lists the branches having a draft put aside.
+///
+/// ## Arguments
+/// * `drafts_dir` - `&Path` - The folder of the drafts
+///
+/// ## Returns
+/// * `Vec<String>` - The branches, sorted
+pub fn list_stored_drafts(drafts_dir: &Path) -> Vec<String> {
+    let mut folders: Vec<PathBuf> = vec![drafts_dir.to_path_buf()];
+    let mut branches: Vec<String> = Vec::new();
+
+    while let Some(folder) = folders.pop() {
+        let Ok(entries) = std::fs::read_dir(&folder) else {
+            continue;
+        };
+
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                folders.push(path);
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == DRAFT_EXTENSION)
+            {
+                if let Ok(relative) = path.with_extension("").strip_prefix(drafts_dir) {
+                    branches.push(relative.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+    }
+
+    branches.sort();
+    branches
+}
+
+///
+/// # `draft_title`
+/// Returns the first line of a draft, its header.
+///
+/// ## Arguments
+/// * `draft` - `&str` - The draft
+///
+/// ## Returns
+/// * `&str` - The first line that is neither blank nor a `#` comment
+pub fn draft_title(draft: &str) -> &str {
+    draft
+        .lines()
+        .find(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .unwrap_or_default()
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::{draft_title, list_stored_drafts, read_owner, sync_draft, DraftSwap};
+
+    #[test]
+    fn test_sync_draft() {
+        let root = std::env::temp_dir().join(format!("git-commands-drafts-{}", std::process::id()));
+        let drafts_dir = root.join("drafts");
+        let draft_path = root.join("commit_message.md");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(&draft_path, "[1] (feat on login) Add\n").unwrap();
+
+        // The first run only records the branch
+        assert_eq!(sync_draft(&drafts_dir, &draft_path, "feat/login"), Ok(None));
+        assert_eq!(read_owner(&drafts_dir).as_deref(), Some("feat/login"));
+
+        assert_eq!(
+            sync_draft(&drafts_dir, &draft_path, "main"),
+            Ok(Some(DraftSwap {
+                previous: "feat/login".to_string(),
+                restored: false,
+                kept: None
+            }))
+        );
+        assert!(!draft_path.exists());
+        assert_eq!(list_stored_drafts(&drafts_dir), vec!["feat/login"]);
+
+        assert_eq!(
+            sync_draft(&drafts_dir, &draft_path, "feat/login"),
+            Ok(Some(DraftSwap {
+                previous: "main".to_string(),
+                restored: true,
+                kept: None
+            }))
+        );
+        assert_eq!(
+            std::fs::read_to_string(&draft_path).unwrap(),
+            "[1] (feat on login) Add\n"
+        );
+        assert!(list_stored_drafts(&drafts_dir).is_empty());
+
+        // A draft already put aside for the branch is not overwritten
+        std::fs::create_dir_all(drafts_dir.join("feat")).unwrap();
+        std::fs::write(
+            drafts_dir.join("feat/login.md"),
+            "[0] (feat on login) Old\n",
+        )
+        .unwrap();
+
+        let swap = sync_draft(&drafts_dir, &draft_path, "main")
+            .unwrap()
+            .unwrap();
+        let kept = swap.kept.unwrap();
+
+        assert_eq!(
+            list_stored_drafts(&drafts_dir),
+            vec!["feat/login".to_string(), kept.clone()]
+        );
+        assert_eq!(
+            std::fs::read_to_string(drafts_dir.join(format!("{kept}.md"))).unwrap(),
+            "[0] (feat on login) Old\n"
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_draft_title() {
+        assert_eq!(
+            draft_title("\n# 2 file(s) staged\n[3] (fix on login) Fix\n\n- `a.rs`:\n"),
+            "[3] (fix on login) Fix"
+        );
+        assert_eq!(draft_title(""), "");
+    }
+}
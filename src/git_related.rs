@@ -2,6 +2,7 @@
 /// # `git_related.rs`
 /// Contains functions related to git.
 // Imports ================================================================================= Imports
+use crate::runner::{run_streaming, RunOptions, RunOutput};
 use crate::utils::read_file;
 
 use ansi_term::Colour::{Green, Red};
@@ -9,6 +10,134 @@ use std::collections::HashSet;
 use std::io::{Error, ErrorKind, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::Duration;
+
+// Constants  ===========================================================================  Constants
+/// The `git status --porcelain` codes of the unmerged (conflicted) paths
+pub const UNMERGED_CODES: [&str; 7] = ["DD", "AU", "UD", "UA", "DU", "AA", "UU"];
+/// File keeping the output of the last failed command, inside the git directory
+const TRANSCRIPT_FILE: &str = "git-commands/last-failure.log";
+
+// Structs ================================================================================= Structs
+/// A commit listed by `git log`.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// The full SHA
+    pub sha: String,
+    /// The author name
+    pub author: String,
+    /// The author date (`YYYY-MM-DD`)
+    pub date: String,
+    /// The full commit message
+    pub message: String,
+    /// The files touched by the commit, with their name at that commit
+    pub files: Vec<String>,
+}
+
+impl LogEntry {
+    ///
+    /// # `sample`
+    /// Builds a commit for the tests.
+    ///
+    /// ## Arguments
+    /// * `message` - `&str` - The commit message
+    /// * `author` - `&str` - The author name
+    /// * `date` - `&str` - The author date (`YYYY-MM-DD`)
+    /// * `files` - `&[&str]` - The touched files
+    ///
+    /// ## Returns
+    /// * `LogEntry` - The commit
+    #[cfg(test)]
+    pub fn sample(message: &str, author: &str, date: &str, files: &[&str]) -> Self {
+        LogEntry {
+            sha: "0123456789".to_string(),
+            author: author.to_string(),
+            date: date.to_string(),
+            message: message.to_string(),
+            files: files.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+/// The options of the `git commit` command.
+#[derive(Debug, Default, Clone)]
+pub struct CommitOptions {
+    /// `--signoff`
+    pub signoff: bool,
+    /// `-S` if empty, `--gpg-sign=<key>` otherwise
+    pub gpg_sign: Option<String>,
+    /// `--no-verify`
+    pub no_verify: bool,
+    /// `--allow-empty`
+    pub allow_empty: bool,
+    /// `--author=<author>`
+    pub author: Option<String>,
+    /// `--date=<date>`
+    pub date: Option<String>,
+    /// `--amend`
+    pub amend: bool,
+    /// Args passed as is to `git commit`
+    pub extra_args: Vec<String>,
+    /// Kill `git commit` after this delay, not passed as an arg
+    pub timeout: Option<Duration>,
+}
+
+impl CommitOptions {
+    ///
+    /// # `to_args`
+    /// Converts the options into `git commit` args.
+    ///
+    /// ## Returns
+    /// * `Vec<String>` - The args
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = Vec::new();
+
+        if self.signoff {
+            args.push("--signoff".to_string());
+        }
+
+        match self.gpg_sign.as_deref() {
+            Some("") => args.push("-S".to_string()),
+            Some(key) => args.push(format!("--gpg-sign={key}")),
+            None => {}
+        }
+
+        if self.no_verify {
+            args.push("--no-verify".to_string());
+        }
+
+        if self.allow_empty {
+            args.push("--allow-empty".to_string());
+        }
+
+        if let Some(author) = &self.author {
+            args.push(format!("--author={author}"));
+        }
+
+        if let Some(date) = &self.date {
+            args.push(format!("--date={date}"));
+        }
+
+        if self.amend {
+            args.push("--amend".to_string());
+        }
+
+        args.extend(self.extra_args.iter().cloned());
+
+        args
+    }
+}
+
+/// The parts of a branch name.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BranchInfo {
+    /// The commit type prefix (`feat` in `feat/PROJ-12-login`)
+    pub kind: Option<String>,
+    /// The ticket ID (`PROJ-12` in `feat/PROJ-12-login`)
+    pub ticket: Option<String>,
+    /// The rest of the name (`login` in `feat/PROJ-12-login`)
+    pub slug: String,
+}
 
 // Functions  ===========================================================================  Functions
 
@@ -65,36 +194,78 @@ pub fn add_with_exclude(files_to_exclude: &Vec<String>, verbose: bool) -> (u32,
     (u32::try_from(staged_count).unwrap(), excluded_count)
 }
 
+///
+/// # `save_transcript`
+/// Saves the output of a failed git command, for the error reports.
+///
+/// ## Arguments
+/// * `output` - `&RunOutput` - The result of the command, its output is marked partial if it timed out
+fn save_transcript(output: &RunOutput) {
+    let Some(path) = get_git_dir().map(|git_dir| git_dir.join(TRANSCRIPT_FILE)) else {
+        return;
+    };
+
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+
+    let transcript = if output.timed_out {
+        format!(
+            "# Partial output: the command timed out and was killed.\n{}",
+            output.transcript
+        )
+    } else {
+        output.transcript.clone()
+    };
+
+    if std::fs::write(&path, transcript).is_ok() {
+        eprintln!("The output was saved in {}", path.display());
+    }
+}
+
 ///
 /// # commit
 /// Commits the changes.
 ///
 /// ## Arguments
 /// * `message` - `String` - The commit message
+/// * `options` - `&CommitOptions` - The `git commit` options
 /// * `verbose` - `bool` - If the commit should be verbose or not
 ///
 /// ## Returns
 /// * `Result<(), String>` - The result of the commit
-pub fn commit(message: &str, verbose: bool) -> Result<bool, String> {
+pub fn commit(message: &str, options: &CommitOptions, verbose: bool) -> Result<bool, String> {
     if verbose {
         println!("Commiting...");
     }
 
-    let final_args: Vec<&str> = vec!["commit", "-m", message];
-
-    let command = Command::new("git")
-        .args(final_args)
-        .output()
-        .expect("failed to execute process");
-
-    if command.status.success() {
+    let mut final_args: Vec<String> =
+        vec!["commit".to_string(), "-m".to_string(), message.to_string()];
+    final_args.extend(options.to_args());
+
+    // The hooks output is streamed live
+    let output = run_streaming(
+        "git",
+        &final_args,
+        &RunOptions {
+            verbose,
+            timeout: options.timeout,
+            ..RunOptions::default()
+        },
+    );
+
+    if output.success {
         println!("{}", Green.bold().paint("Commit successful."));
 
         Ok(true)
     } else {
         println!("{}", Red.bold().paint("Commit failed."));
 
-        eprintln!("Error: {}", String::from_utf8_lossy(&command.stderr));
+        if output.timed_out {
+            eprintln!("Error: `git commit` timed out.");
+        }
+
+        save_transcript(&output);
 
         Err("Commit failed.".to_string())
     }
@@ -106,56 +277,744 @@ pub fn commit(message: &str, verbose: bool) -> Result<bool, String> {
 ///
 /// ## Arguments
 /// * `args` - `Option<Vec<String>>` - The args to pass to the command
+/// * `timeout` - `Option<Duration>` - Kill `git push` after this delay
 /// * `verbose` - `bool` - If the push should be verbose or not
 ///
 /// ## Returns
-/// * `Result<(), String>` - The result of the push
-pub fn push(args: Option<Vec<String>>, verbose: bool) -> Result<(), String> {
+/// * `Result<(), String>` - The result of the push, the git error output if it failed
+pub fn push(
+    args: Option<Vec<String>>,
+    timeout: Option<Duration>,
+    verbose: bool,
+) -> Result<(), String> {
     if verbose {
         println!("\nPushing...");
     }
 
     // Final args for the `git push` command
-    let mut final_args: Vec<String> = vec!["push".to_string()];
+    // The error output is captured, git only shows its progress on a TTY unless asked
+    let mut final_args: Vec<String> = vec!["push".to_string(), "--progress".to_string()];
     final_args.extend(args.unwrap_or_default());
 
-    // Command
+    // The progress and the hooks output are streamed live
+    let output = run_streaming(
+        "git",
+        &final_args,
+        &RunOptions {
+            verbose,
+            timeout,
+            ..RunOptions::default()
+        },
+    );
+
+    if output.success {
+        println!("{}", Green.bold().paint("Push successful."));
+
+        Ok(())
+    } else {
+        println!("{}", Red.bold().paint("Push failed."));
+
+        if output.timed_out {
+            eprintln!("Error: `git push` timed out.");
+        }
+
+        save_transcript(&output);
+
+        Err(output.stderr.trim().to_string())
+    }
+}
+
+///
+/// # `get_diff_files`
+/// Returns the files changed in a diff.
+///
+/// ## Arguments
+/// * `args` - `&[&str]` - The `git diff` arguments (`--cached`, `origin/main..HEAD`, ...)
+///
+/// ## Returns
+/// * `Vec<String>` - The changed files, relative to the project root
+pub fn get_diff_files(args: &[&str]) -> Vec<String> {
     let command = Command::new("git")
-        .args(final_args)
+        .args(["diff", "--name-only"])
+        .args(args)
+        .output()
+        .expect("failed to execute process");
+
+    String::from_utf8_lossy(&command.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+///
+/// # `read_added_diff`
+/// Reads a diff without context lines, to look at the added lines.
+///
+/// ## Arguments
+/// * `args` - `&[&str]` - The `git diff` arguments (`--cached`, `HEAD -- src/`, ...)
+///
+/// ## Returns
+/// * `String` - The diff
+pub fn read_added_diff(args: &[&str]) -> String {
+    let command = Command::new("git")
+        .args(["diff", "-U0", "--no-color", "--no-ext-diff"])
+        .args(args)
+        .output()
+        .expect("failed to execute process");
+
+    String::from_utf8_lossy(&command.stdout).to_string()
+}
+
+///
+/// # `read_diff_summary`
+/// Reads a summary of the changes.
+///
+/// ## Arguments
+/// * `args` - `&[&str]` - The summary options (`--cached`, `--numstat`, `--summary`, ...)
+///
+/// ## Returns
+/// * `String` - The `git diff` output
+pub fn read_diff_summary(args: &[&str]) -> String {
+    let command = Command::new("git")
+        .args(["diff", "--no-color"])
+        .args(args)
+        .output()
+        .expect("failed to execute process");
+
+    String::from_utf8_lossy(&command.stdout).to_string()
+}
+
+///
+/// # `get_staged_size`
+/// Returns the size of the staged version of a file.
+///
+/// ## Arguments
+/// * `file` - `&str` - The file, relative to the project root
+///
+/// ## Returns
+/// * `Option<u64>` - The size in bytes, `None` if the file isn't in the index
+pub fn get_staged_size(file: &str) -> Option<u64> {
+    let output = Command::new("git")
+        .args(["cat-file", "-s", &format!(":{file}")])
+        .output()
+        .expect("failed to execute process");
+
+    if output.status.success() {
+        String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+    } else {
+        None
+    }
+}
+
+///
+/// # `read_blob`
+/// Reads the content of a file at a revision.
+///
+/// ## Arguments
+/// * `spec` - `&str` - The blob (`HEAD:src/main.rs`, `:src/main.rs` for the staged version)
+///
+/// ## Returns
+/// * `Option<String>` - The content, `None` if the file doesn't exist there
+pub fn read_blob(spec: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["show", spec])
+        .output()
+        .expect("failed to execute process");
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        None
+    }
+}
+
+///
+/// # `read_worktree_diff`
+/// Reads the diff of files against `HEAD`, the untracked ones included.
+///
+/// ## Arguments
+/// * `files` - `&[String]` - The files
+///
+/// ## Returns
+/// * `String` - The diff, without context lines
+pub fn read_worktree_diff(files: &[String]) -> String {
+    let mut args: Vec<&str> = vec!["HEAD", "--"];
+    args.extend(files.iter().map(String::as_str));
+    let mut diff = read_added_diff(&args);
+
+    let command = Command::new("git")
+        .args(["ls-files", "--others", "--exclude-standard", "--"])
+        .args(files)
+        .output()
+        .expect("failed to execute process");
+
+    // `--no-index` shows an untracked file as added to `/dev/null`
+    for file in String::from_utf8_lossy(&command.stdout).lines() {
+        diff.push_str(&read_added_diff(&["--no-index", "/dev/null", file]));
+    }
+
+    diff
+}
+
+///
+/// # `get_upstream`
+/// Returns the upstream of the current branch.
+///
+/// ## Returns
+/// * `Option<String>` - The upstream (`origin/main`), `None` if the branch has none
+pub fn get_upstream() -> Option<String> {
+    let command = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"])
         .output()
         .expect("failed to execute process");
 
     if command.status.success() {
-        println!("{}", Green.bold().paint("Push successful."));
+        Some(String::from_utf8_lossy(&command.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
+
+///
+/// # `get_remote_branch_sha`
+/// Returns the last fetched value of a remote branch.
+///
+/// ## Arguments
+/// * `remote` - `&str` - The remote
+/// * `branch` - `&str` - The branch
+///
+/// ## Returns
+/// * `Option<String>` - The hash, `None` if the branch was never fetched
+pub fn get_remote_branch_sha(remote: &str, branch: &str) -> Option<String> {
+    let command = Command::new("git")
+        .args([
+            "rev-parse",
+            "--verify",
+            "-q",
+            &format!("refs/remotes/{remote}/{branch}"),
+        ])
+        .output()
+        .expect("failed to execute process");
+
+    if command.status.success() {
+        Some(String::from_utf8_lossy(&command.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
+
+///
+/// # `fetch_branch`
+/// Fetches a branch of a remote.
+///
+/// ## Arguments
+/// * `remote` - `&str` - The remote
+/// * `branch` - `&str` - The branch
+///
+/// ## Returns
+/// * `bool` - `true` if the fetch succeeded
+pub fn fetch_branch(remote: &str, branch: &str) -> bool {
+    Command::new("git")
+        .args(["fetch", "-q", remote, branch])
+        .output()
+        .expect("failed to execute process")
+        .status
+        .success()
+}
 
+///
+/// # `count_commits`
+/// Counts the commits of a range.
+///
+/// ## Arguments
+/// * `range` - `&str` - The range (`HEAD..origin/main`)
+///
+/// ## Returns
+/// * `usize` - The number of commits, `0` if the range is invalid
+pub fn count_commits(range: &str) -> usize {
+    let command = Command::new("git")
+        .args(["rev-list", "--count", range])
+        .output()
+        .expect("failed to execute process");
+
+    String::from_utf8_lossy(&command.stdout)
+        .trim()
+        .parse()
+        .unwrap_or(0)
+}
+
+///
+/// # `pull_rebase`
+/// Pulls a remote branch and rebases the current branch on it.
+///
+/// ## Arguments
+/// * `remote` - `&str` - The remote
+/// * `branch` - `&str` - The branch
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the pull
+pub fn pull_rebase(remote: &str, branch: &str) -> Result<(), String> {
+    let args: Vec<String> = ["pull", "--rebase", remote, branch]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+    // The progress and the conflicts are streamed live
+    let output = run_streaming("git", &args, &RunOptions::default());
+
+    if output.success {
         Ok(())
     } else {
-        println!("{}", Red.bold().paint("Push failed."));
+        Err(output.stderr.trim().to_string())
+    }
+}
+
+///
+/// # `add_files`
+/// Adds the given files to the git index.
+///
+/// ## Arguments
+/// * `files` - `&[String]` - The files to add
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the add
+pub fn add_files(files: &[String]) -> Result<(), String> {
+    let command = Command::new("git")
+        .arg("add")
+        .arg("--")
+        .args(files)
+        .output()
+        .expect("failed to execute process");
+
+    if command.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&command.stderr).trim().to_string())
+    }
+}
+
+///
+/// # `run_sequencer_action`
+/// Continues or aborts the operation in progress (`git merge --continue`, ...).
+/// The editor is skipped, the prepared message is used as is.
+///
+/// ## Arguments
+/// * `command` - `&str` - The git command of the operation (`merge`, `rebase`, ...)
+/// * `action` - `&str` - `--continue` or `--abort`
+/// * `verbose` - `bool` - Verbose the operation
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the command
+pub fn run_sequencer_action(command: &str, action: &str, verbose: bool) -> Result<(), String> {
+    if verbose {
+        println!("Running git {command} {action}...");
+    }
+
+    // The hooks and the conflicts are streamed live
+    let output = run_streaming(
+        "git",
+        &[command.to_string(), action.to_string()],
+        &RunOptions {
+            verbose,
+            env: vec![("GIT_EDITOR".to_string(), "true".to_string())],
+            ..RunOptions::default()
+        },
+    );
+
+    if output.success {
+        Ok(())
+    } else if output.stderr.trim().is_empty() {
+        Err(output.transcript.trim().to_string())
+    } else {
+        Err(output.stderr.trim().to_string())
+    }
+}
+
+///
+/// # `unstage_all`
+/// Removes every change from the index, leaving the working tree untouched.
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the reset
+pub fn unstage_all() -> Result<(), String> {
+    let command = Command::new("git")
+        .arg("reset")
+        .arg("-q")
+        .output()
+        .expect("failed to execute process");
+
+    if command.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&command.stderr).trim().to_string())
+    }
+}
+
+///
+/// # `get_head_sha`
+/// Returns the hash of `HEAD`.
+///
+/// ## Returns
+/// * `Option<String>` - The hash, `None` if the repository has no commit yet
+pub fn get_head_sha() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--verify", "-q", "HEAD"])
+        .output()
+        .expect("failed to execute process");
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
+
+///
+/// # `write_index_tree`
+/// Saves the index as a tree object, to restore it later.
+///
+/// ## Returns
+/// * `Result<String, String>` - The tree hash
+pub fn write_index_tree() -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("write-tree")
+        .output()
+        .expect("failed to execute process");
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+///
+/// # `restore_state`
+/// Moves `HEAD` back and restores the index saved by `write_index_tree`.
+/// The working tree is left untouched.
+///
+/// ## Arguments
+/// * `head` - `Option<&str>` - The commit to move back to, `None` to empty the branch
+/// * `tree` - `&str` - The saved index tree
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the restoration
+pub fn restore_state(head: Option<&str>, tree: &str) -> Result<(), String> {
+    let reset = match head {
+        Some(head) => Command::new("git")
+            .args(["reset", "-q", "--soft", head])
+            .output(),
+        None => Command::new("git")
+            .args(["update-ref", "-d", "HEAD"])
+            .output(),
+    }
+    .expect("failed to execute process");
+
+    if !reset.status.success() {
+        return Err(String::from_utf8_lossy(&reset.stderr).trim().to_string());
+    }
+
+    let read_tree = Command::new("git")
+        .args(["read-tree", tree])
+        .output()
+        .expect("failed to execute process");
+
+    if read_tree.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&read_tree.stderr)
+            .trim()
+            .to_string())
+    }
+}
+
+///
+/// # `find_unchanged_paths`
+/// Returns the paths that don't appear in the git status.
+/// A path inside an untracked directory (`?? dir/`) counts as changed,
+/// and so do both sides of a rename (`R  old -> new`).
+///
+/// ## Arguments
+/// * `paths` - `&[String]` - The paths to look for
+/// * `status` - `&str` - The `git status --porcelain` output
+///
+/// ## Returns
+/// * `Vec<String>` - The paths without changes
+pub fn find_unchanged_paths(paths: &[String], status: &str) -> Vec<String> {
+    let mut changed: Vec<String> = Vec::new();
+    for line in status.lines() {
+        let Some((_, path)) = parse_status_line(line) else {
+            continue;
+        };
+
+        if let Some((old_path, _)) = line.get(3..).and_then(|paths| paths.split_once(" -> ")) {
+            changed.push(old_path.trim_matches('"').to_string());
+        }
+        changed.push(path);
+    }
+
+    paths
+        .iter()
+        .filter(|path| {
+            !changed.iter().any(|changed_path| {
+                changed_path == *path
+                    || (changed_path.ends_with('/') && path.starts_with(changed_path.as_str()))
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+///
+/// # `create_annotated_tag`
+/// Creates an annotated tag on `HEAD`.
+///
+/// ## Arguments
+/// * `tag` - `&str` - The tag name
+/// * `message` - `&str` - The tag message
+/// * `verbose` - `bool` - If the operation should be verbose or not
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the tag creation
+pub fn create_annotated_tag(tag: &str, message: &str, verbose: bool) -> Result<(), String> {
+    if verbose {
+        println!("Tagging {tag}...");
+    }
+
+    let command = Command::new("git")
+        .args(["tag", "-a", tag, "-m", message])
+        .output()
+        .expect("failed to execute process");
+
+    if command.status.success() {
+        println!("{} {tag}", Green.bold().paint("Tag created:"));
+
+        Ok(())
+    } else {
+        println!("{}", Red.bold().paint("Tag failed."));
 
         eprintln!("Error: {}", String::from_utf8_lossy(&command.stderr));
 
-        Err("Push failed.".to_string())
+        Err("Tag failed.".to_string())
     }
 }
 
 ///
-/// # `stash_and_mabye_pop`
-/// Stashes the changes and maybe pop them.
+/// # `rebase_autosquash`
+/// Runs a non-interactive `git rebase --autosquash` onto the given commit,
+/// so the `fixup!` and `squash!` commits are melded into their targets.
+///
+/// ## Arguments
+/// * `onto` - `&str` - The commit to rebase onto
+/// * `verbose` - `bool` - If the rebase should be verbose or not
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the rebase
+pub fn rebase_autosquash(onto: &str, verbose: bool) -> Result<(), String> {
+    if verbose {
+        println!("Rebasing onto {onto}...");
+    }
+
+    // Accept the todo list as is, and keep the `squash!` messages without editing
+    let command = Command::new("git")
+        .args(["rebase", "--interactive", "--autosquash", onto])
+        .env("GIT_SEQUENCE_EDITOR", "true")
+        .env("GIT_EDITOR", "true")
+        .output()
+        .expect("failed to execute process");
+
+    if command.status.success() {
+        println!("{}", Green.bold().paint("Autosquash successful."));
+
+        Ok(())
+    } else {
+        println!("{}", Red.bold().paint("Autosquash failed."));
+
+        eprintln!("Error: {}", String::from_utf8_lossy(&command.stderr));
+
+        Err("Autosquash failed.".to_string())
+    }
+}
+
+///
+/// # `stash_and_mabye_pop`
+/// Stashes the changes and maybe pop them.
+///
+/// ## Arguments
+/// * `pop` - `bool` - If the stash should be popped.
+pub fn stash_and_maybe_pop(pop: bool) {
+    let mut args = vec!["stash".to_string()];
+    if pop {
+        args.push("pop".to_string());
+    } else {
+        args.push("-u".to_string());
+    }
+
+    let _ = run_streaming(
+        "git",
+        &args,
+        &RunOptions {
+            passthrough: true,
+            ..RunOptions::default()
+        },
+    );
+}
+
+///
+/// # `checkout_conflict_side`
+/// Replaces a conflicted file with one side of the conflict.
+///
+/// ## Arguments
+/// * `file` - `&str` - The conflicted file
+/// * `side` - `&str` - `--ours` or `--theirs`
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the checkout
+pub fn checkout_conflict_side(file: &str, side: &str) -> Result<(), String> {
+    let command = Command::new("git")
+        .args(["checkout", side, "--", file])
+        .output()
+        .expect("failed to execute process");
+
+    if command.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&command.stderr).trim().to_string())
+    }
+}
+
+///
+/// # `remove_file`
+/// Removes a file from the working tree and the index.
+///
+/// ## Arguments
+/// * `file` - `&str` - The file
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the removal
+pub fn remove_file(file: &str) -> Result<(), String> {
+    let command = Command::new("git")
+        .args(["rm", "-q", "--", file])
+        .output()
+        .expect("failed to execute process");
+
+    if command.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&command.stderr).trim().to_string())
+    }
+}
+
+///
+/// # `run_merge_tool`
+/// Launches the configured `merge.tool` on a conflicted file.
+///
+/// ## Arguments
+/// * `file` - `&str` - The conflicted file
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the merge tool
+pub fn run_merge_tool(file: &str) -> Result<(), String> {
+    let status = Command::new("git")
+        .args(["mergetool", "--no-prompt", "--", file])
+        .status()
+        .expect("failed to execute process");
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err("The merge tool failed.".to_string())
+    }
+}
+
+///
+/// # `read_stash_list`
+/// Reads the stash entries, one `reference`, `age`, `subject` line per entry
+/// (separated by `\x1f`).
+///
+/// ## Returns
+/// * `String` - The stash list
+pub fn read_stash_list() -> String {
+    let command = Command::new("git")
+        .args(["stash", "list", "--format=%gd%x1f%cr%x1f%gs"])
+        .output()
+        .expect("failed to execute process");
+
+    String::from_utf8_lossy(&command.stdout).to_string()
+}
+
+///
+/// # `get_stash_files`
+/// Returns the files of a stash entry, untracked ones included.
+///
+/// ## Arguments
+/// * `reference` - `&str` - The stash entry (`stash@{0}`)
+///
+/// ## Returns
+/// * `Vec<String>` - The files
+pub fn get_stash_files(reference: &str) -> Vec<String> {
+    let command = Command::new("git")
+        .args([
+            "stash",
+            "show",
+            "--name-only",
+            "--include-untracked",
+            reference,
+        ])
+        .output()
+        .expect("failed to execute process");
+
+    String::from_utf8_lossy(&command.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+///
+/// # `show_stash_diff`
+/// Prints the diff of a stash entry, untracked files included.
+///
+/// ## Arguments
+/// * `reference` - `&str` - The stash entry (`stash@{0}`)
+pub fn show_stash_diff(reference: &str) {
+    let _ = Command::new("git")
+        .args(["stash", "show", "-p", "--include-untracked", reference])
+        .status()
+        .expect("failed to execute process");
+}
+
+///
+/// # `run_stash_command`
+/// Runs a `git stash` subcommand.
 ///
 /// ## Arguments
-/// * `pop` - `bool` - If the stash should be popped.
-pub fn stash_and_maybe_pop(pop: bool) {
-    let mut args = vec!["stash".to_string()];
-    if pop {
-        args.push("pop".to_string());
-    } else {
-        args.push("-u".to_string());
+/// * `args` - `&[String]` - The `git stash` arguments (`pop stash@{1}`, ...)
+/// * `verbose` - `bool` - Verbose the operation
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the command
+pub fn run_stash_command(args: &[String], verbose: bool) -> Result<(), String> {
+    if verbose {
+        println!("Running git stash {}...", args.join(" "));
     }
 
-    let _ = Command::new("git")
+    let command = Command::new("git")
+        .arg("stash")
         .args(args)
         .output()
         .expect("failed to execute process");
+
+    if verbose {
+        print!("{}", String::from_utf8_lossy(&command.stdout));
+    }
+
+    if command.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&command.stderr).trim().to_string())
+    }
 }
 
 ///
@@ -165,13 +1024,16 @@ pub fn stash_and_maybe_pop(pop: bool) {
 /// ## Arguments
 /// * `branch` - `String` - The branch to switch to
 pub fn switch_branch(branch: String) {
-    let command = Command::new("git")
-        .arg("switch")
-        .arg(branch)
-        .output()
-        .expect("failed to execute process");
-
-    if !command.status.success() {
+    let output = run_streaming(
+        "git",
+        &["switch".to_string(), branch],
+        &RunOptions {
+            passthrough: true,
+            ..RunOptions::default()
+        },
+    );
+
+    if !output.success {
         println!("{}", Red.bold().paint("Failed to switch branch."));
     }
 }
@@ -210,6 +1072,49 @@ pub fn format_branch_name(commit_types: &[&str; 4], branch: &str) -> String {
     formatted_branch
 }
 
+/// # `parse_branch_name`
+/// Parses a branch name with the configured regex.
+/// The `type`, `ticket` and `slug` named groups are all optional.
+///
+/// ## Arguments
+/// * `pattern` - `&Regex` - The branch name regex
+/// * `branch` - `&str` - The branch name
+///
+/// ## Example
+/// ```rust
+/// let pattern = Regex::new(r"^(?P<type>\w+)/(?P<ticket>[A-Z]+-\d+)-(?P<slug>.*)$").unwrap();
+///
+/// let info = parse_branch_name(&pattern, "feat/PROJ-1234-add-login");
+///
+/// assert_eq!(info.ticket, Some("PROJ-1234".to_string()));
+/// assert_eq!(info.slug, "add-login");
+/// ```
+///
+/// ## Returns
+/// * `BranchInfo` - The parts, the slug is the whole name if the regex doesn't match
+pub fn parse_branch_name(pattern: &regex::Regex, branch: &str) -> BranchInfo {
+    let Some(captures) = pattern.captures(branch) else {
+        return BranchInfo {
+            kind: None,
+            ticket: None,
+            slug: branch.to_string(),
+        };
+    };
+
+    let group = |name: &str| {
+        captures
+            .name(name)
+            .map(|value| value.as_str().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    BranchInfo {
+        kind: group("type"),
+        ticket: group("ticket"),
+        slug: group("slug").unwrap_or_else(|| branch.to_string()),
+    }
+}
+
 /// # `get_current_branch`
 /// Returns the current git branch.
 ///
@@ -278,6 +1183,296 @@ pub fn get_current_commit_nb() -> u16 {
         .unwrap_or(0)
 }
 
+///
+/// # `get_last_tag`
+/// Returns the most recent tag reachable from `HEAD`.
+///
+/// ## Returns
+/// * `Option<String>` - The tag, `None` if the repository has no tag
+pub fn get_last_tag() -> Option<String> {
+    let output = Command::new("git")
+        .args(["describe", "--tags", "--abbrev=0"])
+        .output()
+        .expect("failed to execute process");
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
+
+///
+/// # `get_commit_messages`
+/// Returns the full messages of the commits in the given range.
+///
+/// ## Arguments
+/// * `range` - `&str` - The revision range (ex: `v1.0.0..HEAD`)
+///
+/// ## Returns
+/// * `Vec<String>` - The commit messages, newest first
+pub fn get_commit_messages(range: &str) -> Vec<String> {
+    let output = Command::new("git")
+        .args(["log", "--format=%B%x00", range])
+        .output()
+        .expect("failed to execute process");
+
+    String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .map(str::trim)
+        .filter(|message| !message.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+///
+/// # `get_log_entries`
+/// Returns the commits listed by `git log`, with the files they touched.
+///
+/// ## Arguments
+/// * `args` - `&[&str]` - The extra `git log` args (range, `--follow`, `-- path`, ...)
+///
+/// ## Returns
+/// * `Vec<LogEntry>` - The commits, newest first
+pub fn get_log_entries(args: &[&str]) -> Vec<LogEntry> {
+    let output = Command::new("git")
+        .args([
+            "log",
+            "--name-status",
+            "--date=short",
+            "--format=%x1e%H%x1f%an%x1f%ad%x1f%B%x1f",
+        ])
+        .args(args)
+        .output()
+        .expect("failed to execute process");
+
+    String::from_utf8_lossy(&output.stdout)
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.split('\x1f');
+
+            let sha = fields.next()?.trim().to_string();
+            let author = fields.next()?.to_string();
+            let date = fields.next()?.to_string();
+            let message = fields.next()?.trim().to_string();
+
+            // `--name-status` lines: `M\tpath` or `R100\told\tnew`
+            let files = fields
+                .next()
+                .unwrap_or_default()
+                .lines()
+                .filter_map(|line| line.split('\t').next_back())
+                .filter(|path| !path.is_empty())
+                .map(ToString::to_string)
+                .collect();
+
+            Some(LogEntry {
+                sha,
+                author,
+                date,
+                message,
+                files,
+            })
+        })
+        .collect()
+}
+
+///
+/// # `get_git_dir`
+/// Returns the git directory of the repository (`.git` for most repositories).
+///
+/// ## Returns
+/// * `Option<PathBuf>` - The absolute path of the git directory
+pub fn get_git_dir() -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--absolute-git-dir"])
+        .output()
+        .expect("failed to execute process");
+
+    if output.status.success() {
+        Some(PathBuf::from(
+            String::from_utf8_lossy(&output.stdout).trim(),
+        ))
+    } else {
+        None
+    }
+}
+
+///
+/// # `get_hooks_dir`
+/// Returns the folder git reads the hooks from, `core.hooksPath` included.
+///
+/// ## Returns
+/// * `Option<PathBuf>` - The absolute path of the hooks folder
+pub fn get_hooks_dir() -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--path-format=absolute", "--git-path", "hooks"])
+        .output()
+        .expect("failed to execute process");
+
+    if output.status.success() {
+        Some(PathBuf::from(
+            String::from_utf8_lossy(&output.stdout).trim(),
+        ))
+    } else {
+        None
+    }
+}
+
+///
+/// # `get_recent_authors`
+/// Returns the authors of the recent commits, most recent first.
+///
+/// ## Arguments
+/// * `count` - `usize` - Number of commits to look at
+///
+/// ## Returns
+/// * `Vec<String>` - The authors (`Name <email>`), without duplicates
+pub fn get_recent_authors(count: usize) -> Vec<String> {
+    let output = Command::new("git")
+        .args(["log", "-n", &count.to_string(), "--format=%aN <%aE>"])
+        .output()
+        .expect("failed to execute process");
+
+    let mut authors: Vec<String> = Vec::new();
+    for author in String::from_utf8_lossy(&output.stdout).lines() {
+        if !authors.iter().any(|known| known == author) {
+            authors.push(author.to_string());
+        }
+    }
+
+    authors
+}
+
+///
+/// # `get_config_value`
+/// Reads a git config value.
+///
+/// ## Arguments
+/// * `key` - `&str` - The config key (ex: `user.email`)
+///
+/// ## Returns
+/// * `Option<String>` - The value, `None` if it isn't set
+pub fn get_config_value(key: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["config", "--get", key])
+        .output()
+        .expect("failed to execute process");
+
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    if output.status.success() && !value.is_empty() {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+///
+/// # `get_commit_files`
+/// Returns the files touched by a commit.
+///
+/// ## Arguments
+/// * `revision` - `&str` - The commit (ex: `HEAD`)
+///
+/// ## Returns
+/// * `Vec<String>` - The files
+pub fn get_commit_files(revision: &str) -> Vec<String> {
+    let output = Command::new("git")
+        .args(["show", "--name-only", "--format=", revision])
+        .output()
+        .expect("failed to execute process");
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+///
+/// # `get_default_branch`
+/// Returns the branch the feature branches start from.
+/// The remote `HEAD` is used, then `main` or `master`.
+///
+/// ## Returns
+/// * `String` - The base branch
+pub fn get_default_branch() -> String {
+    let output = Command::new("git")
+        .args(["symbolic-ref", "--short", "refs/remotes/origin/HEAD"])
+        .output()
+        .expect("failed to execute process");
+
+    if output.status.success() {
+        return String::from_utf8_lossy(&output.stdout).trim().to_string();
+    }
+
+    let branches = get_branches_list();
+    if branches.iter().any(|branch| branch == "main") {
+        "main".to_string()
+    } else {
+        "master".to_string()
+    }
+}
+
+///
+/// # `get_merge_base`
+/// Returns the best common ancestor of `HEAD` and a branch.
+///
+/// ## Arguments
+/// * `branch` - `&str` - The other branch
+///
+/// ## Returns
+/// * `Option<String>` - The commit SHA, `None` if they have no common ancestor
+pub fn get_merge_base(branch: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["merge-base", "HEAD", branch])
+        .output()
+        .expect("failed to execute process");
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
+
+///
+/// # `get_signature_status`
+/// Returns the signature status of the `HEAD` commit.
+///
+/// ## Returns
+/// * `String` - A readable status (ex: `good signature by Tom`)
+pub fn get_signature_status() -> String {
+    let output = Command::new("git")
+        .args(["log", "-1", "--format=%G?%x1f%GS"])
+        .output()
+        .expect("failed to execute process");
+
+    let output = String::from_utf8_lossy(&output.stdout);
+    let (code, signer) = output
+        .trim()
+        .split_once('\x1f')
+        .unwrap_or((output.trim(), ""));
+
+    let status = match code {
+        "G" => "good signature",
+        "U" => "good signature with unknown validity",
+        "B" => "bad signature",
+        "X" => "good signature, expired",
+        "Y" => "good signature by an expired key",
+        "R" => "good signature by a revoked key",
+        "E" => "signature can't be checked (missing key)",
+        _ => "not signed",
+    };
+
+    if signer.is_empty() {
+        status.to_string()
+    } else {
+        format!("{status} by {signer}")
+    }
+}
+
 // PROCESSING FUNCTIONS ====================================================== PROCESSING FUNCTIONS
 ///
 /// # `process_git_status`
@@ -288,7 +1483,7 @@ pub fn get_current_commit_nb() -> u16 {
 /// * `message` - The git status
 ///
 /// ## Returns
-/// * `Vec<String>` - The modified / added files
+/// * `Vec<String>` - The modified / added files, without the conflicted ones
 pub fn process_git_status(message: &str) -> Vec<String> {
     // Regex to match the modified files and the added files
     let regex_rule = regex::Regex::new(r"^[MTARCU][A-Z\?\! ]\s(.*)$").unwrap();
@@ -296,7 +1491,10 @@ pub fn process_git_status(message: &str) -> Vec<String> {
     message
         .lines()
         .filter_map(|line| {
-            if regex_rule.is_match(line) {
+            // The conflicted files are listed apart, see `get_conflicted_files`
+            if UNMERGED_CODES.iter().any(|code| line.starts_with(code)) {
+                None
+            } else if regex_rule.is_match(line) {
                 Some(
                     regex_rule
                         .captures(line)
@@ -313,6 +1511,24 @@ pub fn process_git_status(message: &str) -> Vec<String> {
         .collect()
 }
 
+///
+/// # `get_conflicted_files`
+/// Returns the conflicted files of the git status.
+///
+/// ## Arguments
+/// * `message` - `&str` - The git status
+///
+/// ## Returns
+/// * `Vec<String>` - The conflicted files
+pub fn get_conflicted_files(message: &str) -> Vec<String> {
+    message
+        .lines()
+        .filter_map(parse_status_line)
+        .filter(|(code, _)| UNMERGED_CODES.contains(&code.as_str()))
+        .map(|(_, path)| path)
+        .collect()
+}
+
 ///
 /// # `process_deleted_files`
 /// Processes the deleted files.
@@ -341,6 +1557,29 @@ pub fn process_deteted_files(message: &str) -> Vec<String> {
     deleted_files
 }
 
+///
+/// # `parse_status_line`
+/// Splits a `git status --porcelain` line into its status code and its path.
+/// For renames and copies, the new path is returned.
+///
+/// ## Arguments
+/// * `line` - `&str` - The git status line
+///
+/// ## Returns
+/// * `Option<(String, String)>` - The two letters status code and the path
+pub fn parse_status_line(line: &str) -> Option<(String, String)> {
+    let code = line.get(..2)?;
+    let path = line.get(3..)?;
+
+    let path = path.rsplit(" -> ").next().unwrap_or(path).trim_matches('"');
+
+    if path.is_empty() {
+        None
+    } else {
+        Some((code.to_string(), path.to_string()))
+    }
+}
+
 ///
 /// # `process_gitignore_file`
 /// Processes the gitignore file.
@@ -413,6 +1652,30 @@ pub fn read_git_status() -> String {
     }
 }
 
+///
+/// # `read_git_diff`
+/// Reads the diff of the staged and unstaged changes against `HEAD`.
+///
+/// ## Returns
+/// * `String` - The diff, the staged diff if the repository has no commit yet
+pub fn read_git_diff() -> String {
+    let command = Command::new("git")
+        .args(["diff", "HEAD"])
+        .output()
+        .expect("failed to execute process");
+
+    if command.status.success() {
+        return String::from_utf8_lossy(&command.stdout).to_string();
+    }
+
+    let command = Command::new("git")
+        .args(["diff", "--cached"])
+        .output()
+        .expect("failed to execute process");
+
+    String::from_utf8_lossy(&command.stdout).to_string()
+}
+
 // Other functions ===============================================================  Other functions
 /// # `find_project_root`
 /// Finds the project root.
@@ -555,10 +1818,12 @@ mod tests {
     use std::path::Path;
 
     use super::{
-        add_with_exclude, find_git_project_root, format_branch_name, get_branches_list,
-        get_current_branch, get_current_commit_nb, process_deteted_files, process_git_status,
-        process_gitignore_file,
+        add_with_exclude, find_git_project_root, find_unchanged_paths, format_branch_name,
+        get_branches_list, get_conflicted_files, get_current_branch, get_current_commit_nb,
+        parse_branch_name, process_deteted_files, process_git_status, process_gitignore_file,
+        CommitOptions,
     };
+    use crate::config::BranchConfig;
 
     #[test]
     fn test_format_branch_name() {
@@ -579,6 +1844,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_commit_options_to_args() {
+        let options = CommitOptions {
+            signoff: true,
+            gpg_sign: Some(String::new()),
+            author: Some("Ana <ana@mail.com>".to_string()),
+            extra_args: vec!["--quiet".to_string()],
+            ..CommitOptions::default()
+        };
+
+        assert_eq!(
+            options.to_args(),
+            vec!["--signoff", "-S", "--author=Ana <ana@mail.com>", "--quiet"]
+        );
+
+        let options = CommitOptions {
+            gpg_sign: Some("ABCD1234".to_string()),
+            no_verify: true,
+            ..CommitOptions::default()
+        };
+
+        assert_eq!(
+            options.to_args(),
+            vec!["--gpg-sign=ABCD1234", "--no-verify"]
+        );
+    }
+
+    #[test]
+    fn test_parse_branch_name() {
+        let pattern = regex::Regex::new(&BranchConfig::default().pattern).unwrap();
+
+        let info = parse_branch_name(&pattern, "feat/PROJ-1234-add-login");
+        assert_eq!(info.kind, Some("feat".to_string()));
+        assert_eq!(info.ticket, Some("PROJ-1234".to_string()));
+        assert_eq!(info.slug, "add-login");
+
+        let info = parse_branch_name(&pattern, "fix/typo");
+        assert_eq!(info.ticket, None);
+        assert_eq!(info.slug, "typo");
+
+        let info = parse_branch_name(&pattern, "tom/feat/x");
+        assert_eq!(info.kind, None);
+        assert_eq!(info.slug, "tom/feat/x");
+
+        let info = parse_branch_name(&pattern, "main");
+        assert_eq!(info.kind, None);
+        assert_eq!(info.slug, "main");
+    }
+
     #[test]
     fn test_get_current_branch() {
         assert_eq!(get_current_branch(), "master");
@@ -611,7 +1925,6 @@ mod tests {
             vec![
                 "src/main.rs",
                 "src/utils.rs",
-                "src/bla.rs",
                 "src/blu.rs",
                 "src/bly.rs",
                 "src/pae.rs",
@@ -673,4 +1986,31 @@ mod tests {
 
         assert_eq!(project_root.is_ok(), true);
     }
+
+    #[test]
+    fn test_find_unchanged_paths() {
+        let status = " M src/main.rs\n D old.rs\n?? docs/\nR  a.rs -> b.rs\n";
+        let paths: Vec<String> = [
+            "src/main.rs",
+            "old.rs",
+            "docs/guide.md",
+            "b.rs",
+            "README.md",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+        assert_eq!(find_unchanged_paths(&paths, status), vec!["README.md"]);
+    }
+
+    #[test]
+    fn test_get_conflicted_files() {
+        let status = "UU src/main.rs\nM  src/utils.rs\nAA src/new.rs\nDU src/old.rs\n";
+
+        assert_eq!(
+            get_conflicted_files(status),
+            vec!["src/main.rs", "src/new.rs", "src/old.rs"]
+        );
+    }
 }
@@ -0,0 +1,187 @@
+///
+/// # `grouping.rs`
+/// Groups the file bullets of a generated draft under directory headings.
+///
+/// A heading is a bullet on a directory (`` - `src/`: ``), its description covers
+/// every file under it, the file bullets then can be left blank.
+// Imports ================================================================================= Imports
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::config::Grouping;
+use crate::scope::Package;
+
+// Structs  ===============================================================================  Structs
+/// Files listed under the same heading.
+#[derive(Debug, PartialEq)]
+pub struct BulletGroup {
+    /// The heading directory (`src/`), `None` for the files listed without heading
+    pub heading: Option<String>,
+    /// The files
+    pub files: Vec<String>,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `heading_of`
+/// Returns the heading a file is listed under.
+///
+/// ## Arguments
+/// * `file` - `&str` - The file, relative to the project root
+/// * `grouping` - `Grouping` - The grouping
+/// * `packages` - `&[Package]` - The workspace packages
+///
+/// ## Returns
+/// * `Option<String>` - The heading directory, `None` for the files without heading
+fn heading_of(file: &str, grouping: Grouping, packages: &[Package]) -> Option<String> {
+    match grouping {
+        Grouping::Flat => None,
+        Grouping::Directory => file
+            .split_once('/')
+            .map(|(directory, _)| format!("{directory}/")),
+        // The deepest package containing the file
+        Grouping::Crate => packages
+            .iter()
+            .filter(|package| package.path != "." && Path::new(file).starts_with(&package.path))
+            .max_by_key(|package| package.path.len())
+            .map(|package| format!("{}/", package.path.trim_end_matches('/'))),
+    }
+}
+
+///
+/// # `group_bullets`
+/// Groups the files of a draft.
+/// The files without heading come first, then the headings in path order.
+///
+/// ## Arguments
+/// * `files` - `&[String]` - The files, relative to the project root
+/// * `grouping` - `Grouping` - The grouping
+/// * `packages` - `&[Package]` - The workspace packages, for the crate grouping
+///
+/// ## Returns
+/// * `Vec<BulletGroup>` - The groups
+pub fn group_bullets(
+    files: &[String],
+    grouping: Grouping,
+    packages: &[Package],
+) -> Vec<BulletGroup> {
+    let mut ungrouped: Vec<String> = Vec::new();
+    let mut headings: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for file in files {
+        let Some(heading) = heading_of(file, grouping, packages) else {
+            ungrouped.push(file.clone());
+            continue;
+        };
+
+        headings.entry(heading).or_default().push(file.clone());
+    }
+
+    let mut groups: Vec<BulletGroup> = Vec::new();
+
+    if !ungrouped.is_empty() {
+        groups.push(BulletGroup {
+            heading: None,
+            files: ungrouped,
+        });
+    }
+
+    groups.extend(headings.into_iter().map(|(heading, files)| BulletGroup {
+        heading: Some(heading),
+        files,
+    }));
+
+    groups
+}
+
+///
+/// # `write_bullets`
+/// Writes the headings and the file bullets of a draft.
+///
+/// ## Arguments
+/// * `groups` - `&[BulletGroup]` - The groups
+/// * `deleted_files` - `&[String]` - The deleted files, written as such
+///
+/// ## Returns
+/// * `String` - The bullets
+pub fn write_bullets(groups: &[BulletGroup], deleted_files: &[String]) -> String {
+    let mut bullets = String::new();
+
+    for group in groups {
+        if let Some(heading) = &group.heading {
+            bullets.push_str(&format!("- `{heading}`:\n\n\t\n\n"));
+        }
+
+        for file in &group.files {
+            if deleted_files.contains(file) {
+                bullets.push_str(&format!("- `{file}`: deleted\n\n"));
+            } else {
+                bullets.push_str(&format!("- `{file}`:\n\n\t\n\n"));
+            }
+        }
+    }
+
+    bullets
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::{group_bullets, write_bullets, BulletGroup};
+    use crate::config::Grouping;
+    use crate::scope::Package;
+
+    fn files() -> Vec<String> {
+        [
+            "README.md",
+            "src/cmd/run.rs",
+            "src/main.rs",
+            "crates/core/src/lib.rs",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+    }
+
+    fn headings(groups: &[BulletGroup]) -> Vec<Option<&str>> {
+        groups
+            .iter()
+            .map(|group| group.heading.as_deref())
+            .collect()
+    }
+
+    #[test]
+    fn test_group_bullets() {
+        assert_eq!(
+            headings(&group_bullets(&files(), Grouping::Flat, &[])),
+            vec![None]
+        );
+        assert_eq!(
+            headings(&group_bullets(&files(), Grouping::Directory, &[])),
+            vec![None, Some("crates/"), Some("src/")]
+        );
+
+        let packages = vec![Package {
+            name: "core".to_string(),
+            path: "crates/core".to_string(),
+        }];
+        let groups = group_bullets(&files(), Grouping::Crate, &packages);
+
+        assert_eq!(headings(&groups), vec![None, Some("crates/core/")]);
+        assert_eq!(groups[0].files.len(), 3);
+    }
+
+    #[test]
+    fn test_write_bullets() {
+        let groups = group_bullets(
+            &["a.rs".to_string(), "src/b.rs".to_string()],
+            Grouping::Directory,
+            &[],
+        );
+
+        assert_eq!(
+            write_bullets(&groups, &["a.rs".to_string()]),
+            "- `a.rs`: deleted\n\n- `src/`:\n\n\t\n\n- `src/b.rs`:\n\n\t\n\n"
+        );
+    }
+}
@@ -0,0 +1,398 @@
+///
+/// # `guards.rs`
+/// Checks the staged content before `commit`: large files, leftover conflict markers,
+/// trailing whitespace and CRLF, new binaries and executable bit changes.
+///
+/// Each guard warns, blocks the commit or is off. The findings are also written
+/// as `#` comments under the file bullets of the generated draft.
+// Imports ================================================================================= Imports
+use std::collections::BTreeMap;
+
+use ansi_term::Colour::{Red, Yellow};
+
+use crate::config::{GuardLevel, GuardsConfig};
+use crate::draft::annotate_bullets;
+use crate::git_related::{get_diff_files, get_staged_size, read_added_diff, read_diff_summary};
+use crate::secrets::{parse_added_lines, AddedLine};
+
+// Constants  ===========================================================================  Constants
+/// Number of line numbers listed in a finding
+const LISTED_LINES: usize = 5;
+
+// Structs  ===============================================================================  Structs
+/// A guard.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Guard {
+    LargeFile,
+    ConflictMarkers,
+    Whitespace,
+    Binary,
+    Executable,
+}
+
+/// Something a guard found in a staged file.
+#[derive(Debug, PartialEq)]
+pub struct GuardFinding {
+    /// The guard
+    pub guard: Guard,
+    /// The file, relative to the project root
+    pub file: String,
+    /// What was found
+    pub detail: String,
+}
+
+impl Guard {
+    ///
+    /// # `level`
+    /// Returns the configured level of the guard.
+    ///
+    /// ## Arguments
+    /// * `config` - `&GuardsConfig` - The `[guards]` configuration
+    ///
+    /// ## Returns
+    /// * `GuardLevel` - The level
+    pub fn level(self, config: &GuardsConfig) -> GuardLevel {
+        match self {
+            Guard::LargeFile => config.large_files,
+            Guard::ConflictMarkers => config.conflict_markers,
+            Guard::Whitespace => config.whitespace,
+            Guard::Binary => config.binaries,
+            Guard::Executable => config.executable,
+        }
+    }
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `list_lines`
+/// Lists the first line numbers.
+///
+/// ## Arguments
+/// * `numbers` - `&[usize]` - The line numbers
+///
+/// ## Returns
+/// * `String` - The list (`3, 9, 12, 20, 21 +4`)
+fn list_lines(numbers: &[usize]) -> String {
+    let mut list = numbers
+        .iter()
+        .take(LISTED_LINES)
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    if numbers.len() > LISTED_LINES {
+        list.push_str(&format!(" +{}", numbers.len() - LISTED_LINES));
+    }
+
+    list
+}
+
+///
+/// # `check_added_lines`
+/// Looks for conflict markers, trailing whitespace and CRLF on the added lines.
+///
+/// ## Arguments
+/// * `added_lines` - `&[AddedLine]` - The added lines
+///
+/// ## Returns
+/// * `Vec<GuardFinding>` - One finding per file and problem
+pub fn check_added_lines(added_lines: &[AddedLine]) -> Vec<GuardFinding> {
+    // file → (markers, trailing whitespace, CRLF) line numbers
+    let mut problems: BTreeMap<&str, [Vec<usize>; 3]> = BTreeMap::new();
+
+    for added_line in added_lines {
+        let content = added_line.content.as_str();
+        let entry = problems.entry(&added_line.file).or_default();
+
+        if content.starts_with("<<<<<<<") || content.starts_with(">>>>>>>") {
+            entry[0].push(added_line.number);
+        }
+
+        let without_cr = content.strip_suffix('\r');
+        if without_cr.unwrap_or(content).ends_with([' ', '\t']) {
+            entry[1].push(added_line.number);
+        }
+        if without_cr.is_some() {
+            entry[2].push(added_line.number);
+        }
+    }
+
+    let mut findings: Vec<GuardFinding> = Vec::new();
+
+    for (file, [markers, trailing, crlf]) in problems {
+        if !markers.is_empty() {
+            findings.push(GuardFinding {
+                guard: Guard::ConflictMarkers,
+                file: file.to_string(),
+                detail: format!("conflict markers on line(s) {}", list_lines(&markers)),
+            });
+        }
+
+        if !trailing.is_empty() {
+            findings.push(GuardFinding {
+                guard: Guard::Whitespace,
+                file: file.to_string(),
+                detail: format!("trailing whitespace on line(s) {}", list_lines(&trailing)),
+            });
+        }
+
+        if !crlf.is_empty() {
+            findings.push(GuardFinding {
+                guard: Guard::Whitespace,
+                file: file.to_string(),
+                detail: format!("CRLF line endings on {} line(s)", crlf.len()),
+            });
+        }
+    }
+
+    findings
+}
+
+///
+/// # `parse_binaries`
+/// Returns the binary files of a `git diff --numstat` output.
+///
+/// ## Arguments
+/// * `numstat` - `&str` - The numstat output
+///
+/// ## Returns
+/// * `Vec<String>` - The binary files
+pub fn parse_binaries(numstat: &str) -> Vec<String> {
+    numstat
+        .lines()
+        .filter_map(|line| line.strip_prefix("-\t-\t"))
+        .map(ToString::to_string)
+        .collect()
+}
+
+///
+/// # `parse_mode_changes`
+/// Returns the executable bit changes of a `git diff --summary` output.
+///
+/// ## Arguments
+/// * `summary` - `&str` - The summary output
+///
+/// ## Returns
+/// * `Vec<(String, bool)>` - The files and whether they became executable
+pub fn parse_mode_changes(summary: &str) -> Vec<(String, bool)> {
+    summary
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("mode change "))
+        .filter_map(|change| {
+            // `100644 => 100755 path`
+            let (modes, file) = change.split_once(" => ")?.1.split_once(' ')?;
+
+            Some((file.to_string(), modes == "100755"))
+        })
+        .collect()
+}
+
+///
+/// # `collect_findings`
+/// Runs the guards that aren't off.
+///
+/// ## Arguments
+/// * `config` - `&GuardsConfig` - The `[guards]` configuration
+/// * `diff` - `&str` - The diff of the changes, without context lines
+/// * `numstat` - `&str` - The `--numstat` output of the added files
+/// * `summary` - `&str` - The `--summary` output of the changes
+/// * `sizes` - `&[(String, u64)]` - The sizes of the changed files, in bytes
+///
+/// ## Returns
+/// * `Vec<GuardFinding>` - The findings
+pub fn collect_findings(
+    config: &GuardsConfig,
+    diff: &str,
+    numstat: &str,
+    summary: &str,
+    sizes: &[(String, u64)],
+) -> Vec<GuardFinding> {
+    let mut findings: Vec<GuardFinding> = Vec::new();
+
+    for (file, size) in sizes {
+        if *size > config.max_size_kb * 1024 {
+            findings.push(GuardFinding {
+                guard: Guard::LargeFile,
+                file: file.clone(),
+                detail: format!(
+                    "{:.1} MB, over {} KB, consider Git LFS",
+                    *size as f64 / (1024.0 * 1024.0),
+                    config.max_size_kb
+                ),
+            });
+        }
+    }
+
+    findings.extend(check_added_lines(&parse_added_lines(diff)));
+
+    for file in parse_binaries(numstat) {
+        findings.push(GuardFinding {
+            guard: Guard::Binary,
+            file,
+            detail: "new binary file".to_string(),
+        });
+    }
+
+    for (file, executable) in parse_mode_changes(summary) {
+        findings.push(GuardFinding {
+            guard: Guard::Executable,
+            file,
+            detail: if executable {
+                "made executable".to_string()
+            } else {
+                "no longer executable".to_string()
+            },
+        });
+    }
+
+    findings.retain(|finding| finding.guard.level(config) != GuardLevel::Off);
+
+    findings
+}
+
+///
+/// # `scan_staged`
+/// Runs the guards on the staged changes.
+///
+/// ## Arguments
+/// * `config` - `&GuardsConfig` - The `[guards]` configuration
+///
+/// ## Returns
+/// * `Vec<GuardFinding>` - The findings
+pub fn scan_staged(config: &GuardsConfig) -> Vec<GuardFinding> {
+    let sizes: Vec<(String, u64)> = get_diff_files(&["--cached", "--diff-filter=d"])
+        .into_iter()
+        .filter_map(|file| get_staged_size(&file).map(|size| (file, size)))
+        .collect();
+
+    collect_findings(
+        config,
+        &read_added_diff(&["--cached"]),
+        &read_diff_summary(&["--cached", "--numstat", "--diff-filter=A"]),
+        &read_diff_summary(&["--cached", "--summary"]),
+        &sizes,
+    )
+}
+
+///
+/// # `report_findings`
+/// Prints the findings.
+///
+/// ## Arguments
+/// * `findings` - `&[GuardFinding]` - The findings
+/// * `config` - `&GuardsConfig` - The `[guards]` configuration
+///
+/// ## Returns
+/// * `bool` - `true` if no finding blocks the commit
+pub fn report_findings(findings: &[GuardFinding], config: &GuardsConfig) -> bool {
+    let mut blocked = false;
+
+    for finding in findings {
+        if finding.guard.level(config) == GuardLevel::Block {
+            blocked = true;
+            eprintln!(
+                "{} `{}` {}",
+                Red.bold().paint("Blocked:"),
+                finding.file,
+                finding.detail
+            );
+        } else {
+            println!(
+                "{} `{}` {}",
+                Yellow.bold().paint("Warning:"),
+                finding.file,
+                finding.detail
+            );
+        }
+    }
+
+    if blocked {
+        eprintln!("Fix the staged files, change the `[guards]` levels or pass --skip-guards.");
+    }
+
+    !blocked
+}
+
+///
+/// # `annotate_draft`
+/// Writes the findings as `#` comments under the bullets of their files.
+///
+/// ## Arguments
+/// * `draft` - `&str` - The draft
+/// * `findings` - `&[GuardFinding]` - The findings
+///
+/// ## Returns
+/// * `String` - The annotated draft
+pub fn annotate_draft(draft: &str, findings: &[GuardFinding]) -> String {
+    let mut notes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for finding in findings {
+        notes
+            .entry(finding.file.clone())
+            .or_default()
+            .push(format!("# ⚠ {}", finding.detail));
+    }
+
+    annotate_bullets(draft, &notes)
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::{annotate_draft, collect_findings, parse_mode_changes, Guard};
+    use crate::config::{GuardLevel, GuardsConfig};
+
+    const DIFF: &str = "diff --git a/src/main.rs b/src/main.rs\n\
+        --- a/src/main.rs\n\
+        +++ b/src/main.rs\n\
+        @@ -9,0 +10,4 @@\n\
+        +<<<<<<< HEAD\n\
+        +    let a = 1; \n\
+        +>>>>>>> login\n\
+        +    let b = 2;\r\n";
+
+    #[test]
+    fn test_collect_findings() {
+        let config = GuardsConfig {
+            executable: GuardLevel::Off,
+            ..GuardsConfig::default()
+        };
+        let numstat = "-\t-\tlogo.png\n3\t0\tsrc/main.rs\n";
+        let summary = " mode change 100644 => 100755 build.sh\n";
+        let sizes = vec![("video.mp4".to_string(), 8 * 1024 * 1024)];
+
+        let findings = collect_findings(&config, DIFF, numstat, summary, &sizes);
+        let guards: Vec<Guard> = findings.iter().map(|finding| finding.guard).collect();
+
+        assert_eq!(
+            guards,
+            vec![
+                Guard::LargeFile,
+                Guard::ConflictMarkers,
+                Guard::Whitespace,
+                Guard::Whitespace,
+                Guard::Binary,
+            ]
+        );
+        assert_eq!(findings[1].detail, "conflict markers on line(s) 10, 12");
+    }
+
+    #[test]
+    fn test_parse_mode_changes() {
+        assert_eq!(
+            parse_mode_changes(" mode change 100755 => 100644 run.sh\n create mode 100644 a.rs\n"),
+            vec![("run.sh".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn test_annotate_draft() {
+        let findings = collect_findings(&GuardsConfig::default(), DIFF, "", "", &[]);
+        let draft = "[3] (fix on login)\n\n- `src/main.rs`:\n\n\t\n";
+
+        assert_eq!(
+            annotate_draft(draft, &findings[..1]),
+            "[3] (fix on login)\n\n- `src/main.rs`:\n# ⚠ conflict markers on line(s) 10, 12\n\n\t\n"
+        );
+    }
+}
@@ -0,0 +1,175 @@
+///
+/// # `history.rs`
+/// Contains the functions used by the `history` subcommand.
+///
+/// The messages generated by this tool store a description for each file,
+/// this module lists those descriptions to answer "why did this file change ?".
+// Imports ================================================================================= Imports
+use std::path::Path;
+
+use ansi_term::Colour::{Green, Yellow};
+
+use crate::draft::{is_group_heading, parse_file_descriptions};
+use crate::git_related::{get_log_entries, LogEntry};
+use crate::utils::check_for_file_in_folder;
+
+// Structs ================================================================================= Structs
+/// A commit that described the requested path.
+#[derive(Debug)]
+pub struct HistoryEntry {
+    /// The commit
+    pub commit: LogEntry,
+    /// The described paths (as named at that commit) and their descriptions
+    pub descriptions: Vec<(String, String)>,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `describes_path`
+/// Checks if a described path matches the requested path.
+/// A group heading (`src/`) matches the files under it.
+///
+/// ## Arguments
+/// * `described` - `&str` - The path written in the commit message
+/// * `path` - `&str` - The requested path (`.` for the whole project)
+/// * `names` - `&[String]` - The names of the requested file at that commit (renames)
+///
+/// ## Returns
+/// * `bool` - If the description is about the requested path
+fn describes_path(described: &str, path: &str, names: &[String]) -> bool {
+    path == "."
+        || described == path
+        || names.iter().any(|name| name == described)
+        || check_for_file_in_folder(Path::new(described), Path::new(path))
+        || (is_group_heading(described)
+            && (path.starts_with(described)
+                || names.iter().any(|name| name.starts_with(described))))
+}
+
+///
+/// # `filter_history`
+/// Keeps the commits that described the requested path.
+///
+/// ## Arguments
+/// * `commits` - `Vec<LogEntry>` - The commits that touched the path
+/// * `path` - `&str` - The requested path, relative to the project root
+/// * `grep` - `Option<&str>` - Only keep the descriptions containing this text (case insensitive)
+///
+/// ## Returns
+/// * `Vec<HistoryEntry>` - The matching commits, newest first
+pub fn filter_history(commits: Vec<LogEntry>, path: &str, grep: Option<&str>) -> Vec<HistoryEntry> {
+    let path = path.trim_end_matches('/');
+    let grep = grep.map(str::to_lowercase);
+
+    commits
+        .into_iter()
+        .filter_map(|commit| {
+            let descriptions: Vec<(String, String)> = parse_file_descriptions(&commit.message)
+                .into_iter()
+                .filter(|file| describes_path(&file.path, path, &commit.files))
+                .filter(|file| !file.description.is_empty())
+                .filter(|file| {
+                    grep.as_ref()
+                        .is_none_or(|grep| file.description.to_lowercase().contains(grep))
+                })
+                .map(|file| (file.path, file.description))
+                .collect();
+
+            if descriptions.is_empty() {
+                None
+            } else {
+                Some(HistoryEntry {
+                    commit,
+                    descriptions,
+                })
+            }
+        })
+        .collect()
+}
+
+///
+/// # `history`
+/// Prints every commit that described a file or a directory.
+/// Renames are followed for files.
+///
+/// ## Arguments
+/// * `path` - `&str` - The file or directory, relative to the project root
+/// * `is_dir` - `bool` - Is the path a directory ?
+/// * `grep` - `Option<&str>` - Only keep the descriptions containing this text
+pub fn history(path: &str, is_dir: bool, grep: Option<&str>) {
+    let mut args: Vec<&str> = Vec::new();
+    if !is_dir {
+        // `--follow` only works for a single file
+        args.push("--follow");
+    }
+    args.extend(["--", path]);
+
+    let entries = filter_history(get_log_entries(&args), path, grep);
+
+    if entries.is_empty() {
+        println!("No described commit found for `{path}`.");
+        return;
+    }
+
+    for entry in entries {
+        println!(
+            "{}  {}  {}",
+            Yellow.paint(&entry.commit.sha[..7.min(entry.commit.sha.len())]),
+            entry.commit.date,
+            entry.commit.author
+        );
+
+        for (file, description) in entry.descriptions {
+            println!("  {}", Green.bold().paint(file));
+            println!("      {description}");
+        }
+
+        println!();
+    }
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::filter_history;
+    use crate::git_related::LogEntry;
+
+    #[test]
+    fn test_filter_history() {
+        let commits = vec![
+            LogEntry::sample(
+                "[2] (fix on main) Fix\n\n- `src/new.rs`:\n\n\tFix the parser.\n\n- `README.md`:\n\n\tDoc.\n",
+                "Tom",
+                "2024-01-01",
+                &["src/new.rs"],
+            ),
+            LogEntry::sample(
+                "[1] (feat on main) Add\n\n- `src/old.rs`:\n\n\tAdd the parser.\n",
+                "Tom",
+                "2024-01-01",
+                &["src/old.rs"],
+            ),
+            LogEntry::sample("Initial commit", "Tom", "2024-01-01", &["src/old.rs"]),
+        ];
+
+        let entries = filter_history(commits.clone(), "src/new.rs", None);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[1].descriptions,
+            vec![("src/old.rs".to_string(), "Add the parser.".to_string())]
+        );
+
+        assert_eq!(filter_history(commits.clone(), "src/", None).len(), 2);
+        assert_eq!(filter_history(commits, "src/new.rs", Some("FIX")).len(), 1);
+
+        let grouped = vec![LogEntry::sample(
+            "[3] (feat on main) Add\n\n- `src/`:\n\n\tSplit the parser.\n",
+            "Tom",
+            "2024-01-01",
+            &["src/new.rs"],
+        )];
+
+        assert_eq!(filter_history(grouped, "src/new.rs", None).len(), 1);
+    }
+}
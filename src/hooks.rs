@@ -0,0 +1,348 @@
+///
+/// # `hooks.rs`
+/// Installs the git hooks that drive the generator from a plain `git commit`.
+///
+/// The hooks already present are renamed `<hook>.chained` and run first,
+/// uninstalling puts them back.
+// Imports ================================================================================= Imports
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::render::check_subject;
+
+// Constants  ===========================================================================  Constants
+/// The installed hooks
+pub const HOOK_NAMES: [&str; 2] = ["prepare-commit-msg", "commit-msg"];
+/// Line marking the hooks written by this program
+const HOOK_MARKER: &str = "# Installed by git-commands";
+/// Extension of the hooks that were there before
+const CHAINED_EXTENSION: &str = "chained";
+/// The line of `git commit -v` above which the message ends, without its comment character
+const SCISSORS_LINE: &str = " ------------------------ >8 ------------------------";
+/// Prefixes of the messages written by git, left alone by the lint
+const GIT_PREFIXES: [&str; 6] = [
+    "Merge ",
+    "Revert ",
+    "fixup! ",
+    "squash! ",
+    "amend! ",
+    "Initial commit",
+];
+
+// Structs  ===============================================================================  Structs
+/// The state of a hook.
+#[derive(Debug, PartialEq)]
+pub enum HookState {
+    /// No hook
+    Missing,
+    /// Our hook, `chained` if it runs a previous hook first
+    Installed { chained: bool },
+    /// A hook written by someone else
+    Foreign,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `hook_script`
+/// Writes the script of a hook: it runs the chained hook, then this program.
+///
+/// ## Arguments
+/// * `name` - `&str` - The hook name
+/// * `program` - `&Path` - This program
+///
+/// ## Returns
+/// * `String` - The script
+pub fn hook_script(name: &str, program: &Path) -> String {
+    format!(
+        "#!/bin/sh\n\
+         {HOOK_MARKER}\n\
+         chained=\"$(dirname \"$0\")/{name}.{CHAINED_EXTENSION}\"\n\
+         if [ -x \"$chained\" ]; then\n\
+         \t\"$chained\" \"$@\" || exit $?\n\
+         fi\n\
+         exec '{}' hook {name} \"$@\"\n",
+        program.display().to_string().replace('\'', r"'\''")
+    )
+}
+
+///
+/// # `is_ours`
+/// Checks if a hook was written by this program.
+///
+/// ## Arguments
+/// * `path` - `&Path` - The hook
+///
+/// ## Returns
+/// * `bool` - `true` if the hook holds the marker
+fn is_ours(path: &Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|content| content.contains(HOOK_MARKER))
+}
+
+///
+/// # `hook_state`
+/// Returns the state of a hook.
+///
+/// ## Arguments
+/// * `hooks_dir` - `&Path` - The hooks folder
+/// * `name` - `&str` - The hook name
+///
+/// ## Returns
+/// * `HookState` - The state
+pub fn hook_state(hooks_dir: &Path, name: &str) -> HookState {
+    let path = hooks_dir.join(name);
+
+    if !path.exists() {
+        HookState::Missing
+    } else if is_ours(&path) {
+        HookState::Installed {
+            chained: path.with_extension(CHAINED_EXTENSION).exists(),
+        }
+    } else {
+        HookState::Foreign
+    }
+}
+
+///
+/// # `make_executable`
+/// Sets the executable bits of a file.
+///
+/// ## Arguments
+/// * `path` - `&Path` - The file
+///
+/// ## Returns
+/// * `std::io::Result<()>` - The result of the change
+#[cfg(unix)]
+fn make_executable(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+///
+/// # `install_hooks`
+/// Installs the hooks, the existing ones are chained.
+/// Nothing is touched if one of them can't be chained.
+///
+/// ## Arguments
+/// * `hooks_dir` - `&Path` - The hooks folder
+/// * `program` - `&Path` - This program
+///
+/// ## Returns
+/// * `Result<Vec<String>, String>` - The hooks that were chained
+pub fn install_hooks(hooks_dir: &Path, program: &Path) -> Result<Vec<String>, String> {
+    std::fs::create_dir_all(hooks_dir).map_err(|e| e.to_string())?;
+
+    let foreign: Vec<&str> = HOOK_NAMES
+        .into_iter()
+        .filter(|name| hook_state(hooks_dir, name) == HookState::Foreign)
+        .collect();
+
+    // Checked before any hook is renamed
+    if let Some(chained_path) = foreign
+        .iter()
+        .map(|name| hooks_dir.join(name).with_extension(CHAINED_EXTENSION))
+        .find(|chained_path| chained_path.exists())
+    {
+        return Err(format!(
+            "`{}` already exists, remove it first.",
+            chained_path.display()
+        ));
+    }
+
+    let mut chained: Vec<String> = Vec::new();
+
+    for name in HOOK_NAMES {
+        let path = hooks_dir.join(name);
+
+        if foreign.contains(&name) {
+            let chained_path = path.with_extension(CHAINED_EXTENSION);
+            std::fs::rename(&path, &chained_path).map_err(|e| e.to_string())?;
+            chained.push(name.to_string());
+        }
+
+        std::fs::write(&path, hook_script(name, program)).map_err(|e| e.to_string())?;
+        make_executable(&path).map_err(|e| e.to_string())?;
+    }
+
+    Ok(chained)
+}
+
+///
+/// # `uninstall_hooks`
+/// Removes the hooks, the chained ones are put back.
+///
+/// ## Arguments
+/// * `hooks_dir` - `&Path` - The hooks folder
+///
+/// ## Returns
+/// * `Result<usize, String>` - The number of hooks removed
+pub fn uninstall_hooks(hooks_dir: &Path) -> Result<usize, String> {
+    let mut removed = 0;
+
+    for name in HOOK_NAMES {
+        let path = hooks_dir.join(name);
+
+        if !matches!(hook_state(hooks_dir, name), HookState::Installed { .. }) {
+            continue;
+        }
+
+        std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+        removed += 1;
+
+        let chained_path = path.with_extension(CHAINED_EXTENSION);
+        if chained_path.exists() {
+            std::fs::rename(&chained_path, &path).map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(removed)
+}
+
+///
+/// # `cut_at_scissors`
+/// Removes the scissors line written by `git commit -v` and the diff below it.
+///
+/// ## Arguments
+/// * `buffer` - `&str` - The commit message file content
+///
+/// ## Returns
+/// * `&str` - The content above the scissors line
+pub fn cut_at_scissors(buffer: &str) -> &str {
+    let mut offset = 0;
+
+    for line in buffer.split_inclusive('\n') {
+        // Any comment character (`core.commentChar`), it may take several bytes
+        let content = line.trim_end_matches(['\n', '\r']);
+        let after_comment = content
+            .chars()
+            .next()
+            .map_or("", |first| &content[first.len_utf8()..]);
+        if after_comment.starts_with(SCISSORS_LINE) {
+            return &buffer[..offset];
+        }
+
+        offset += line.len();
+    }
+
+    buffer
+}
+
+///
+/// # `lint_message`
+/// Checks a commit message against the header pattern and the subject rules.
+/// The messages written by git (merges, reverts, fixups) are not checked.
+///
+/// ## Arguments
+/// * `message` - `&str` - The commit message
+/// * `header_rule` - `&Regex` - The header pattern
+/// * `width` - `usize` - The wrap width, `0` to skip the length check
+///
+/// ## Returns
+/// * `Vec<String>` - The problems found
+pub fn lint_message(message: &str, header_rule: &Regex, width: usize) -> Vec<String> {
+    let header = message.lines().next().unwrap_or_default();
+
+    if GIT_PREFIXES.iter().any(|prefix| header.starts_with(prefix)) {
+        return Vec::new();
+    }
+
+    let mut problems = Vec::new();
+
+    if !header_rule.is_match(header) {
+        problems.push(format!(
+            "the header `{header}` doesn't match the pattern `{}`",
+            header_rule.as_str()
+        ));
+    }
+
+    problems.extend(check_subject(message, width));
+
+    problems
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use regex::Regex;
+
+    use super::{
+        cut_at_scissors, hook_state, install_hooks, lint_message, uninstall_hooks, HookState,
+    };
+    use crate::config::FormatConfig;
+
+    #[test]
+    fn test_install_hooks() {
+        let hooks_dir =
+            std::env::temp_dir().join(format!("git-commands-hooks-{}", std::process::id()));
+        std::fs::create_dir_all(&hooks_dir).unwrap();
+        std::fs::write(hooks_dir.join("commit-msg"), "#!/bin/sh\nexit 0\n").unwrap();
+
+        let chained = install_hooks(&hooks_dir, Path::new("/usr/bin/git-commands")).unwrap();
+        assert_eq!(chained, vec!["commit-msg"]);
+        assert_eq!(
+            hook_state(&hooks_dir, "commit-msg"),
+            HookState::Installed { chained: true }
+        );
+        assert_eq!(
+            hook_state(&hooks_dir, "prepare-commit-msg"),
+            HookState::Installed { chained: false }
+        );
+
+        assert_eq!(uninstall_hooks(&hooks_dir).unwrap(), 2);
+        assert_eq!(hook_state(&hooks_dir, "commit-msg"), HookState::Foreign);
+        assert_eq!(
+            hook_state(&hooks_dir, "prepare-commit-msg"),
+            HookState::Missing
+        );
+
+        // A leftover chained hook stops the install before anything is renamed
+        std::fs::write(hooks_dir.join("prepare-commit-msg"), "#!/bin/sh\n").unwrap();
+        std::fs::write(hooks_dir.join("commit-msg.chained"), "#!/bin/sh\n").unwrap();
+
+        assert!(install_hooks(&hooks_dir, Path::new("/usr/bin/git-commands")).is_err());
+        assert_eq!(
+            hook_state(&hooks_dir, "prepare-commit-msg"),
+            HookState::Foreign
+        );
+
+        std::fs::remove_dir_all(&hooks_dir).unwrap();
+    }
+
+    #[test]
+    fn test_lint_message() {
+        let header_rule = Regex::new(&FormatConfig::default().header_pattern).unwrap();
+
+        assert!(lint_message("[3] (feat on login) Add\n", &header_rule, 72).is_empty());
+        assert!(lint_message("Merge branch 'login'\n", &header_rule, 72).is_empty());
+        assert_eq!(lint_message("add stuff\n", &header_rule, 72).len(), 1);
+    }
+
+    #[test]
+    fn test_cut_at_scissors() {
+        let buffer = "[3] (feat on login) Add\n\n# Please enter the commit message.\n# ------------------------ >8 ------------------------\n# Do not modify or remove the line above.\ndiff --git a/a.rs b/a.rs\n+# not a comment\n";
+
+        assert_eq!(
+            cut_at_scissors(buffer),
+            "[3] (feat on login) Add\n\n# Please enter the commit message.\n"
+        );
+        assert_eq!(
+            cut_at_scissors("; ------------------------ >8 ------------------------\ndiff\n"),
+            ""
+        );
+        assert_eq!(
+            cut_at_scissors(
+                "é Add\n§ ------------------------ >8 ------------------------\ndiff\n"
+            ),
+            "é Add\n"
+        );
+        assert_eq!(cut_at_scissors("Add\n"), "Add\n");
+    }
+}
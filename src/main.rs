@@ -13,17 +13,91 @@ mod git_related;
 #[path = "my_theme.rs"]
 mod my_theme;
 
+#[path = "config.rs"]
+mod config;
+
+#[path = "draft.rs"]
+mod draft;
+
+#[path = "release.rs"]
+mod release;
+
+#[path = "history.rs"]
+mod history;
+
+#[path = "stats.rs"]
+mod stats;
+
+#[path = "classify.rs"]
+mod classify;
+
+#[path = "scope.rs"]
+mod scope;
+
+#[path = "pair.rs"]
+mod pair;
+
+#[path = "amend.rs"]
+mod amend;
+
+#[path = "render.rs"]
+mod render;
+
+#[path = "split.rs"]
+mod split;
+
+#[path = "operation.rs"]
+mod operation;
+
+#[path = "resolve.rs"]
+mod resolve;
+
+#[path = "stash.rs"]
+mod stash;
+
+#[path = "push.rs"]
+mod push;
+
+#[path = "runner.rs"]
+mod runner;
+
+#[path = "checks.rs"]
+mod checks;
+
+#[path = "hooks.rs"]
+mod hooks;
+
+#[path = "secrets.rs"]
+mod secrets;
+
+#[path = "guards.rs"]
+mod guards;
+
+#[path = "diffstat.rs"]
+mod diffstat;
+
+#[path = "symbols.rs"]
+mod symbols;
+
+#[path = "grouping.rs"]
+mod grouping;
+
+#[path = "drafts.rs"]
+mod drafts;
+
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::{fs::File, io::prelude::Write};
 
-use ansi_term::Colour::{Green, Red};
-use clap::{Parser, Subcommand};
-use dialoguer::{Confirm, Select};
+use ansi_term::Colour::{Green, Red, Yellow};
+use clap::{Args, Parser, Subcommand};
+use config::{BranchConfig, CommitConfig};
+use dialoguer::{Confirm, MultiSelect, Select};
 use git_related::{
     add_to_git_exclude, add_with_exclude, commit, find_git_project_root, format_branch_name,
-    get_branches_list, get_current_branch, get_current_commit_nb, process_deteted_files,
-    process_git_status, process_gitignore_file, push, read_git_status, stash_and_maybe_pop,
-    switch_branch,
+    get_branches_list, get_current_branch, get_current_commit_nb, parse_branch_name,
+    process_deteted_files, process_git_status, process_gitignore_file, read_git_status,
+    stash_and_maybe_pop, switch_branch, BranchInfo, CommitOptions,
 };
 use utils::check_for_file_in_folder;
 
@@ -31,6 +105,7 @@ use utils::check_for_file_in_folder;
 const GITIGNORE_FILE_PATH: &str = ".gitignore";
 const COMMIT_MESSAGE_FILE: &str = "commit_message.md";
 const COMMITIGNORE_FILE_PATH: &str = ".commitignore";
+const CONFIG_FILE_PATH: &str = ".git-commands.toml";
 const COMMIT_TYPES: [&str; 4] = ["chore", "feat", "fix", "test"];
 
 // Args commands
@@ -78,22 +153,90 @@ enum Commands {
         push: bool,
 
         /// Optional 'push args' argument. Only works if the 'commit' and 'push' arguments are passed.
-        #[arg(short)]
+        #[arg(short, allow_hyphen_values = true)]
         args: Option<Vec<String>>,
+
+        /// Pick the people you paired with and add them as `Co-authored-by:` trailers
+        #[arg(long)]
+        pair: bool,
+
+        /// Regenerate the draft from the last commit merged with the new changes, and amend it
+        #[arg(long, conflicts_with_all = ["fixup", "squash"])]
+        amend: bool,
+
+        /// Create a `fixup!` commit for a commit picked from the branch
+        #[arg(long, conflicts_with = "squash")]
+        fixup: bool,
+
+        /// Stage exactly the files described in the draft, and unstage everything else
+        #[arg(long, conflicts_with = "amend")]
+        from_draft: bool,
+
+        /// Create a `squash!` commit for a commit picked from the branch, with the draft as message
+        #[arg(long)]
+        squash: bool,
+
+        /// Don't run the configured `[[checks]]`
+        #[arg(long)]
+        skip_checks: bool,
+
+        /// Commit even if the staged changes look like they hold secrets
+        #[arg(long)]
+        allow_secrets: bool,
+
+        /// Don't run the `[guards]` on the staged content
+        #[arg(long)]
+        skip_guards: bool,
+
+        /// `git commit` options
+        #[command(flatten)]
+        flags: CommitFlags,
     },
 
+    /// Preview subcommand
+    /// Print the commit message rendered from the `commit_message.md` file.
+    Preview,
+
+    /// Continue subcommand
+    /// Conclude the merge, rebase, cherry-pick or revert in progress with the draft.
+    Continue,
+
+    /// Abort subcommand
+    /// Abort the merge, rebase, cherry-pick or revert in progress.
+    Abort,
+
+    /// Autosquash subcommand
+    /// Meld the `fixup!` and `squash!` commits of the branch into their targets.
+    Autosquash,
+
     /// Generate subcommand
     /// Directly generate the `commit_message.md` file.
     #[command(short_flag = 'g')]
-    Generate,
+    Generate {
+        /// Pick the people you paired with and add them as `Co-authored-by:` trailers
+        #[arg(long)]
+        pair: bool,
+
+        /// Propose one commit section per scope or directory
+        #[arg(long)]
+        split: bool,
+
+        /// Group the file bullets under directory headings, overrides `[draft] grouping`
+        #[arg(long, value_enum)]
+        group: Option<config::Grouping>,
+    },
 
     /// Push subcommand
     /// Push the changes
     #[command(short_flag = 'p')]
     Push {
         /// Optional 'push args' argument. Only works if the 'commit' and 'push' arguments are passed.
-        #[arg(short)]
+        #[arg(short, allow_hyphen_values = true)]
         args: Option<Vec<String>>,
+
+        /// Don't run the configured `[[checks]]`
+        #[arg(long)]
+        skip_checks: bool,
     },
 
     /// Facilitate switching between branches
@@ -108,11 +251,255 @@ enum Commands {
         apply_stash: bool,
     },
 
+    /// Resolve subcommand
+    /// Resolve the conflicted files one by one and stage them.
+    Resolve,
+
+    /// Stash subcommand
+    /// Manage the stash entries, lists them when no action is given.
+    Stash {
+        #[command(subcommand)]
+        action: Option<StashCommands>,
+    },
+
+    /// Hooks subcommand
+    /// Install, uninstall or show the git hooks that drive the generator from `git commit`.
+    Hooks {
+        #[command(subcommand)]
+        action: HooksCommands,
+    },
+
+    /// Drafts subcommand
+    /// List, show or delete the drafts kept for each branch.
+    Drafts {
+        #[command(subcommand)]
+        action: DraftsCommands,
+    },
+
+    /// Run by the installed git hooks
+    #[command(hide = true)]
+    Hook {
+        /// The hook name
+        name: String,
+
+        /// The arguments git passed to the hook
+        #[arg(allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
     /// List files from git status (for shell completion)
     #[command(short_flag = 'l')]
     ListStatus,
+
+    /// Release subcommand
+    /// Propose the next version from the commits since the last tag, bump the manifests,
+    /// commit and create an annotated tag.
+    Release {
+        /// Only print the proposed version and what would be done
+        #[arg(short, long)]
+        dry_run: bool,
+
+        /// Push the release commit and its tag
+        #[arg(short, long)]
+        push: bool,
+
+        /// Optional 'push args' argument. Only works if the 'push' argument is passed.
+        #[arg(short, allow_hyphen_values = true)]
+        args: Option<Vec<String>>,
+    },
+
+    /// History subcommand
+    /// List the commits that described a file or a directory, with their description.
+    History {
+        /// The file or directory
+        path: String,
+
+        /// Only keep the descriptions containing this text
+        #[arg(short, long)]
+        grep: Option<String>,
+    },
+
+    /// Stats subcommand
+    /// Count the commits per type, author, branch and month.
+    Stats {
+        /// The revision range (ex: `v1.0.0..HEAD`), the whole history by default
+        range: Option<String>,
+
+        /// Print the statistics as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Number of most often changed files to show
+        #[arg(short, long, default_value_t = 10)]
+        top: usize,
+    },
+}
+
+/// The actions of the `stash` subcommand.
+#[derive(Subcommand)]
+enum StashCommands {
+    /// List the stash entries with their branch, age and files
+    List,
+
+    /// Show the diff of a stash entry
+    Show,
+
+    /// Stash the changes, untracked files included
+    Push {
+        /// The stash message
+        #[arg(short, long)]
+        message: Option<String>,
+
+        /// Only stash these paths
+        paths: Vec<String>,
+    },
+
+    /// Apply a stash entry and keep it
+    Apply,
+
+    /// Apply a stash entry and drop it
+    Pop,
+
+    /// Drop a stash entry
+    Drop,
+
+    /// Create a branch from a stash entry and drop the entry
+    Branch {
+        /// The new branch name
+        name: String,
+    },
+}
+
+/// The actions of the `hooks` subcommand.
+#[derive(Subcommand)]
+enum HooksCommands {
+    /// Install the hooks, the existing ones are chained
+    Install,
+
+    /// Remove the hooks and restore the chained ones
+    Uninstall,
+
+    /// Show the state of the hooks
+    Status,
+}
+
+/// The actions of the `drafts` subcommand.
+#[derive(Subcommand)]
+enum DraftsCommands {
+    /// List the branches having a draft
+    List,
+
+    /// Print the draft of a branch
+    Show {
+        /// The branch, the current one by default
+        branch: Option<String>,
+    },
+
+    /// Delete the draft of a branch
+    Delete {
+        /// The branch, the current one by default
+        branch: Option<String>,
+    },
 }
+
+/// The `git commit` options of the `commit` subcommand.
+/// The unset ones fall back to the `[commit]` configuration.
+#[derive(Args, Default)]
+struct CommitFlags {
+    /// Add a `Signed-off-by:` trailer
+    #[arg(long)]
+    signoff: bool,
+
+    /// Sign the commit with GPG or SSH, optionally with the given key
+    #[arg(short = 'S', long, num_args = 0..=1, default_missing_value = "")]
+    gpg_sign: Option<String>,
+
+    /// Skip the `pre-commit` and `commit-msg` hooks
+    #[arg(long)]
+    no_verify: bool,
+
+    /// Allow a commit without changes
+    #[arg(long)]
+    allow_empty: bool,
+
+    /// Override the commit author (`Name <email>`)
+    #[arg(long)]
+    author: Option<String>,
+
+    /// Override the author date
+    #[arg(long)]
+    date: Option<String>,
+
+    /// Optional 'commit args' argument, passed as is to `git commit`.
+    #[arg(short = 'x', allow_hyphen_values = true)]
+    commit_args: Option<Vec<String>>,
+}
+
+impl CommitFlags {
+    ///
+    /// # `to_options`
+    /// Merges the flags with the configured defaults.
+    ///
+    /// ## Arguments
+    /// * `config` - `&CommitConfig` - The `[commit]` configuration
+    ///
+    /// ## Returns
+    /// * `CommitOptions` - The `git commit` options
+    fn to_options(&self, config: &CommitConfig) -> CommitOptions {
+        CommitOptions {
+            signoff: self.signoff || config.signoff,
+            gpg_sign: self.gpg_sign.clone().or_else(|| config.gpg_sign.clone()),
+            no_verify: self.no_verify || config.no_verify,
+            allow_empty: self.allow_empty || config.allow_empty,
+            author: self.author.clone().or_else(|| config.author.clone()),
+            date: self.date.clone(),
+            extra_args: self.commit_args.clone().unwrap_or_default(),
+            timeout: config.timeout.map(std::time::Duration::from_secs),
+            ..CommitOptions::default()
+        }
+    }
+}
+
 // Function(s) =========================================================================== Functions
+///
+/// # `is_commitignored`
+/// Checks if a file is excluded from the commit message by the commitignore file.
+///
+/// ## Arguments
+/// * `folder_path` - `&Path` - The project root
+/// * `file` - `&str` - The file
+///
+/// ## Returns
+/// * `bool` - `true` if the file must not appear in the commit message
+fn is_commitignored(folder_path: &Path, file: &str) -> bool {
+    let gitignore_path = folder_path.join(GITIGNORE_FILE_PATH);
+    let comitignore_path = folder_path.join(COMMITIGNORE_FILE_PATH);
+
+    // If the file is not a file in the commitignore file
+    // or is not in a folder in the commitignore file
+    if !comitignore_path.exists() {
+        return false;
+    }
+
+    let mut items_to_ignore: Vec<String> = process_gitignore_file(&gitignore_path);
+    items_to_ignore.append(&mut process_gitignore_file(&comitignore_path));
+
+    // Check if the file/folder is in the commitignore file or gitignore file
+    if items_to_ignore.iter().any(|item| item == file) {
+        return true;
+    }
+
+    // for each item in the commitignore file and gitignore file,
+    // check for file in the folder
+    // for example:
+    // `data/year_2015/puzzles/` in the commitignore file can
+    // exclude `data/year_2015/puzzles/day_01.md` from the commit
+    // and in general `data/year_2015/puzzles/*` from the commit
+    items_to_ignore
+        .iter()
+        .any(|item| check_for_file_in_folder(Path::new(file), Path::new(item)))
+}
+
 ///
 /// # `prepare_commit_msg`
 /// Prepares the commit message.
@@ -120,19 +507,25 @@ enum Commands {
 /// It also adds the modified / added files to the commit message file.
 ///
 /// ## Arguments
-/// * `path` - `&Path` - The source folder
+/// * `path` - `&Path` - The commit message file
+/// * `project_root` - `&Path` - The project root, where the `.commitignore` file is read
 /// * `commit_types` - `&str` - The commit types
+/// * `scope` - `Option<&str>` - The commit scope
+/// * `branch_name` - `&str` - The branch name written in the header
+/// * `trailers` - `&[String]` - The trailers written at the end (`Refs: PROJ-12`)
+/// * `grouping` - `config::Grouping` - The grouping of the file bullets
 /// * `verbose` - `bool` - Verbose the operation
-fn prepare_commit_msg(path: &Path, commit_type: &str, verbose: bool) {
-    // Get the location of the file passed by 'path'
-    // ex: path = /home/user/project/src/main.rs
-    // get the location of the file: /home/user/project/src/
-    let folder_path = path.parent().unwrap();
-
-    // Get the path to the commit message file
-    let gitignore_path = folder_path.join(GITIGNORE_FILE_PATH);
-    let comitignore_path = folder_path.join(COMMITIGNORE_FILE_PATH);
-
+#[allow(clippy::too_many_arguments)]
+fn prepare_commit_msg(
+    path: &Path,
+    project_root: &Path,
+    commit_type: &str,
+    scope: Option<&str>,
+    branch_name: &str,
+    trailers: &[String],
+    grouping: config::Grouping,
+    verbose: bool,
+) {
     // If the COMMIT_MESSAGE_FILE exists
     if path.exists() {
         // Empty the file
@@ -154,7 +547,11 @@ fn prepare_commit_msg(path: &Path, commit_type: &str, verbose: bool) {
         .unwrap();
 
     let commit_number: u16 = get_current_commit_nb() + 1;
-    let branch_name: &str = &format_branch_name(&COMMIT_TYPES, &get_current_branch());
+
+    let commit_type = match scope {
+        Some(scope) => format!("{commit_type}({scope})"),
+        None => commit_type.to_string(),
+    };
 
     if let Err(e) = writeln!(
         commit_file,
@@ -163,52 +560,27 @@ fn prepare_commit_msg(path: &Path, commit_type: &str, verbose: bool) {
         eprintln!("Couldn't write to file: {e}");
     }
 
-    // For each modified file
-    for file in modified_files {
-        // If the file is not a file in the commitignore file
-        // or is not in a folder in the commitignore file
-        if comitignore_path.exists() {
-            let mut items_to_ignore: Vec<String> = process_gitignore_file(&gitignore_path);
-            items_to_ignore.append(&mut process_gitignore_file(&comitignore_path));
-
-            // Check if the file/folder is in the commitignore file or gitignore file
-            if items_to_ignore.contains(&file) {
-                // continue means skip the current iteration
-                continue;
-            }
-
-            // This variable is used to call the 'continue' statement
-            // just before the 'writeln!' macro.
-            // I can't use the 'continue' statement directly in the for loop
-            // because it will skip the next item, not file.
-            let mut need_to_skip = false;
-
-            // for each item in the commitignore file and gitignore file,
-            // check for file in the folder
-            // for example:
-            // `data/year_2015/puzzles/` in the commitignore file can
-            // exclude `data/year_2015/puzzles/day_01.md` from the commit
-            // and in general `data/year_2015/puzzles/*` from the commit
-            for item in items_to_ignore {
-                if check_for_file_in_folder(Path::new(&file), Path::new(&item)) {
-                    need_to_skip = true;
-                }
-            }
+    // The modified files, without the commitignored ones, then the deleted files
+    let mut files: Vec<String> = modified_files
+        .into_iter()
+        .filter(|file| !is_commitignored(project_root, file))
+        .collect();
+    files.extend(deleted_files.iter().cloned());
 
-            if need_to_skip {
-                // Skip the current file so the file is not added to the commit message
-                continue;
-            }
-        }
+    let packages = scope::read_workspace_packages(project_root);
+    let groups = grouping::group_bullets(&files, grouping, &packages);
 
-        if let Err(e) = writeln!(commit_file, "- `{file}`:\n\n\t\n") {
-            eprintln!("Couldn't write to file: {e}");
-        }
+    if let Err(e) = write!(
+        commit_file,
+        "{}",
+        grouping::write_bullets(&groups, &deleted_files)
+    ) {
+        eprintln!("Couldn't write to file: {e}");
     }
 
-    // For each deleted file
-    for file in deleted_files {
-        if let Err(e) = writeln!(commit_file, "- `{file}`: deleted\n") {
+    // Trailers
+    if !trailers.is_empty() {
+        if let Err(e) = writeln!(commit_file, "{}", trailers.join("\n")) {
             eprintln!("Couldn't write to file: {e}");
         }
     }
@@ -227,6 +599,100 @@ fn prepare_commit_msg(path: &Path, commit_type: &str, verbose: bool) {
     }
 }
 
+///
+/// # `prepare_split_commit_msg`
+/// Prepares a split commit message: one section per scope or directory,
+/// with the commit type suggested for its files.
+///
+/// ## Arguments
+/// * `path` - `&Path` - The commit message file
+/// * `config` - `&config::Config` - The configuration
+/// * `branch_info` - `&BranchInfo` - The current branch
+/// * `trailers` - `&[String]` - The trailers written at the end of every section
+/// * `grouping` - `config::Grouping` - The grouping of the file bullets in each section
+/// * `verbose` - `bool` - Verbose the operation
+fn prepare_split_commit_msg(
+    path: &Path,
+    config: &config::Config,
+    branch_info: &BranchInfo,
+    trailers: &[String],
+    grouping: config::Grouping,
+    verbose: bool,
+) {
+    let folder_path = path.parent().unwrap();
+
+    let git_status = read_git_status();
+    let deleted_files: Vec<String> = process_deteted_files(&git_status);
+
+    let mut files: Vec<String> = process_git_status(&git_status)
+        .into_iter()
+        .filter(|file| !is_commitignored(folder_path, file))
+        .collect();
+    files.extend(deleted_files.iter().cloned());
+
+    let packages = scope::read_workspace_packages(folder_path);
+    let groups = split::group_files(&files, &packages, &config.scopes);
+
+    let first_commit_number: u16 = get_current_commit_nb() + 1;
+    let mut sections: Vec<String> = Vec::new();
+
+    for (index, group) in groups.iter().enumerate() {
+        // The status lines of the group, to suggest its commit type
+        let group_status: String = git_status
+            .lines()
+            .filter(|line| {
+                git_related::parse_status_line(line)
+                    .is_some_and(|(_, file)| group.files.contains(&file))
+            })
+            .collect::<Vec<&str>>()
+            .join("\n");
+
+        let commit_type =
+            classify::suggest_commit_type(&group_status, "", &config.classifier.rules)
+                .map(|suggestion| suggestion.commit_type)
+                .or_else(|| branch_commit_type(branch_info))
+                .unwrap_or_else(|| COMMIT_TYPES[0].to_string());
+
+        let commit_type = match &group.scope {
+            Some(scope) => format!("{commit_type}({scope})"),
+            None => commit_type,
+        };
+
+        let mut section = format!(
+            "[{}] ({commit_type} on {})\n\n\n",
+            first_commit_number + index as u16,
+            branch_info.slug
+        );
+
+        section.push_str(&grouping::write_bullets(
+            &grouping::group_bullets(&group.files, grouping, &packages),
+            &deleted_files,
+        ));
+
+        if !trailers.is_empty() {
+            section.push_str(&format!("{}\n", trailers.join("\n")));
+        }
+
+        sections.push(section);
+    }
+
+    if let Err(e) = std::fs::write(
+        path,
+        sections.join(&format!("{}\n", split::SECTION_SEPARATOR)),
+    ) {
+        eprintln!("Couldn't write to file: {e}");
+    }
+
+    if verbose {
+        println!(
+            "{} {} with {} sections ✅ ",
+            COMMIT_MESSAGE_FILE,
+            Green.bold().paint("created"),
+            groups.len()
+        );
+    }
+}
+
 /// # `create_needed_files`
 /// Creates the needed files in the specified project root.
 ///
@@ -293,6 +759,407 @@ fn create_needed_files(path: &PathBuf, verbose: bool) {
     }
 }
 
+///
+/// # `current_branch_info`
+/// Parses the current branch name with the configured pattern.
+/// Falls back to `format_branch_name` if the pattern is invalid, and uses it to
+/// clean the slug when the pattern found no type (`tom/feat/x` → `tom/x`).
+///
+/// ## Arguments
+/// * `config` - `&BranchConfig` - The branch configuration
+///
+/// ## Returns
+/// * `BranchInfo` - The parts of the current branch name
+fn current_branch_info(config: &BranchConfig) -> BranchInfo {
+    let branch = get_current_branch();
+
+    match regex::Regex::new(&config.pattern) {
+        Ok(pattern) => {
+            let mut branch_info = parse_branch_name(&pattern, &branch);
+            if branch_info.kind.is_none() {
+                branch_info.slug = format_branch_name(&COMMIT_TYPES, &branch_info.slug);
+            }
+
+            branch_info
+        }
+        Err(e) => {
+            eprintln!("{} {e}", Red.bold().paint("Invalid branch pattern:"));
+
+            BranchInfo {
+                kind: None,
+                ticket: None,
+                slug: format_branch_name(&COMMIT_TYPES, &branch),
+            }
+        }
+    }
+}
+
+///
+/// # `branch_commit_type`
+/// Returns the type prefix of the branch name when it is a known commit type.
+///
+/// ## Arguments
+/// * `branch_info` - `&BranchInfo` - The parts of the current branch name
+///
+/// ## Returns
+/// * `Option<String>` - The commit type, `None` for a `tom/login` or `release/1.2` branch
+fn branch_commit_type(branch_info: &BranchInfo) -> Option<String> {
+    branch_info
+        .kind
+        .clone()
+        .filter(|kind| COMMIT_TYPES.contains(&kind.as_str()))
+}
+
+///
+/// # `drop_unselectable_rules`
+/// Removes the classifier rules whose type can't be picked, with a warning.
+///
+/// ## Arguments
+/// * `rules` - `&mut BTreeMap<String, Vec<String>>` - The path globs per commit type
+fn drop_unselectable_rules(rules: &mut BTreeMap<String, Vec<String>>) {
+    rules.retain(|commit_type, _| {
+        let selectable = COMMIT_TYPES.contains(&commit_type.as_str());
+        if !selectable {
+            eprintln!(
+                "{} ignoring the `{commit_type}` classifier rule, the commit types are {}.",
+                Yellow.bold().paint("Warning:"),
+                COMMIT_TYPES.join(", ")
+            );
+        }
+
+        selectable
+    });
+}
+
+///
+/// # `warn_missing_ticket`
+/// Warns when the current branch requires a ticket and neither the branch name
+/// nor the commit message has one.
+///
+/// ## Arguments
+/// * `config` - `&BranchConfig` - The branch configuration
+/// * `commit_message` - `&str` - The commit message
+fn warn_missing_ticket(config: &BranchConfig, commit_message: &str) {
+    let branch = get_current_branch();
+    let protected = classify::build_glob_set(&config.require_ticket);
+
+    if !protected.is_match(&branch) || current_branch_info(config).ticket.is_some() {
+        return;
+    }
+
+    let has_trailer = draft::parse_trailers(commit_message)
+        .iter()
+        .any(|(key, _)| *key == config.ticket_trailer);
+
+    if !has_trailer {
+        println!(
+            "{} branch `{branch}` requires a ticket but none was found (no `{}:` trailer).",
+            Yellow.bold().paint("Warning:"),
+            config.ticket_trailer
+        );
+    }
+}
+
+///
+/// # `warn_undescribed_files`
+/// Warns about the file bullets left blank and not covered by a described group
+/// heading: the rendering drops them.
+///
+/// ## Arguments
+/// * `config` - `&config::RenderConfig` - The rendering configuration
+/// * `draft` - `&str` - The draft
+fn warn_undescribed_files(config: &config::RenderConfig, draft: &str) {
+    if !config.drop_empty_bullets {
+        return;
+    }
+
+    for file in draft::undescribed_files(draft) {
+        println!(
+            "{} `{file}` has no description, it is left out of the message.",
+            Yellow.bold().paint("Warning:")
+        );
+    }
+}
+
+///
+/// # `choose_scope`
+/// Infers the scope of the files to commit: the staged files, or the files of the draft
+/// when nothing is staged yet.
+/// The scope is only picked alone when every file belongs to it, otherwise the user
+/// picks the ones to keep.
+///
+/// ## Arguments
+/// * `project_root` - `&Path` - The project root
+/// * `mappings` - `&BTreeMap<String, String>` - The configured path glob → scope map
+///
+/// ## Returns
+/// * `Option<String>` - The scope (comma separated if several were picked)
+fn choose_scope(project_root: &Path, mappings: &BTreeMap<String, String>) -> Option<String> {
+    let mut files: Vec<String> = git_related::get_diff_files(&["--cached"]);
+    if files.is_empty() {
+        let git_status = read_git_status();
+
+        files = process_git_status(&git_status)
+            .into_iter()
+            .filter(|file| !is_commitignored(project_root, file))
+            .chain(process_deteted_files(&git_status))
+            .collect();
+    }
+
+    let packages = scope::read_workspace_packages(project_root);
+    let inferred = scope::infer_scopes(&files, &packages, mappings);
+    let scopes = inferred.scopes;
+
+    match (scopes.len(), inferred.unscoped.len()) {
+        (0, _) => None,
+        (1, 0) => scopes.into_iter().next(),
+        (_, unscoped) => {
+            // A scope doesn't cover the files outside of it, nothing is preselected then
+            if unscoped > 0 {
+                println!(
+                    "{} file(s) outside of any scope ({}).",
+                    unscoped,
+                    inferred.unscoped.join(", ")
+                );
+            }
+
+            let chosen = MultiSelect::with_theme(&my_theme::ColorfulTheme::default())
+                .with_prompt("Scopes")
+                .items(&scopes)
+                .defaults(&vec![unscoped == 0; scopes.len()])
+                .interact()
+                .unwrap();
+
+            if chosen.is_empty() {
+                None
+            } else {
+                Some(
+                    chosen
+                        .into_iter()
+                        .map(|index| scopes[index].clone())
+                        .collect::<Vec<String>>()
+                        .join(","),
+                )
+            }
+        }
+    }
+}
+
+///
+/// # `offer_to_resolve_stash_conflicts`
+/// Offers to resolve the conflicts left by applying a stash entry.
+/// A conflicting pop keeps the stash entry and leaves the conflicts in the tree.
+///
+/// ## Arguments
+/// * `project_root` - `&Path` - The project root
+fn offer_to_resolve_stash_conflicts(project_root: &Path) {
+    let conflicted_files = git_related::get_conflicted_files(&read_git_status());
+
+    if !conflicted_files.is_empty()
+        && Confirm::with_theme(&my_theme::ColorfulTheme::default())
+            .with_prompt(format!(
+                "The stash conflicts with {} file(s), resolve them now ?",
+                conflicted_files.len()
+            ))
+            .default(true)
+            .interact()
+            .unwrap()
+        && resolve::resolve_conflicts(project_root) == 0
+    {
+        println!("The stash was kept, drop it with `stash drop`.");
+    }
+}
+
+///
+/// # `block_on_conflicts`
+/// Exits with the list of the conflicted files if some conflicts remain.
+fn block_on_conflicts() {
+    let conflicted_files = git_related::get_conflicted_files(&read_git_status());
+
+    if !conflicted_files.is_empty() {
+        eprintln!(
+            "{} resolve them with `resolve` or add them first: {}",
+            Red.bold().paint("Conflicts remain:"),
+            conflicted_files.join(", ")
+        );
+        std::process::exit(1);
+    }
+}
+
+///
+/// # `run_checks_or_exit`
+/// Runs the configured checks of an operation, exits if one of them fails.
+///
+/// ## Arguments
+/// * `config` - `&config::Config` - The configuration
+/// * `operation` - `&str` - `commit` or `push`
+/// * `files` - `&[String]` - The changed files
+/// * `project_root` - `&Path` - The project root
+/// * `verbose` - `bool` - Verbose the operation
+fn run_checks_or_exit(
+    config: &config::Config,
+    operation: &str,
+    files: &[String],
+    project_root: &Path,
+    verbose: bool,
+) {
+    if !checks::run_checks(&config.checks, operation, files, project_root, verbose) {
+        eprintln!(
+            "{} fix them or pass --skip-checks.",
+            Red.bold().paint("Checks failed:")
+        );
+        std::process::exit(1);
+    }
+}
+
+///
+/// # `push_or_exit`
+/// Runs the pre-push checks then pushes, exits if either fails.
+///
+/// ## Arguments
+/// * `args` - `&Option<Vec<String>>` - The `git push` arguments
+/// * `config` - `&config::Config` - The configuration
+/// * `project_root` - `&Path` - The project root
+/// * `skip_checks` - `bool` - Don't run the checks
+/// * `verbose` - `bool` - Verbose the operation
+fn push_or_exit(
+    args: &Option<Vec<String>>,
+    config: &config::Config,
+    project_root: &Path,
+    skip_checks: bool,
+    verbose: bool,
+) {
+    if !skip_checks {
+        let files = checks::get_pushed_files();
+        run_checks_or_exit(config, "push", &files, project_root, verbose);
+    }
+
+    if let Err(e) = push::smart_push(args.clone(), &config.push, verbose) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+///
+/// # `fill_commit_buffer`
+/// Fills the buffer of a plain `git commit` with the generated draft.
+/// Nothing is asked: the commit type is the suggested one and every inferred scope is kept.
+///
+/// ## Arguments
+/// * `buffer` - `&Path` - The commit message file git opens in the editor
+/// * `project_root` - `&Path` - The project root
+/// * `config` - `&config::Config` - The configuration
+fn fill_commit_buffer(buffer: &Path, project_root: &Path, config: &config::Config) {
+    // The comments git wrote are kept under the draft
+    let git_comments = std::fs::read_to_string(buffer).unwrap_or_default();
+
+    let branch_info = current_branch_info(&config.branch);
+    let commit_type = classify::suggest_commit_type(
+        &read_git_status(),
+        &git_related::read_git_diff(),
+        &config.classifier.rules,
+    )
+    .map(|suggestion| suggestion.commit_type)
+    .or_else(|| branch_commit_type(&branch_info))
+    .unwrap_or_else(|| COMMIT_TYPES[0].to_string());
+
+    // No prompt in a hook: a scope is only written when it covers every staged file
+    let staged_files = git_related::get_diff_files(&["--cached"]);
+    let packages = scope::read_workspace_packages(project_root);
+    let inferred = scope::infer_scopes(&staged_files, &packages, &config.scopes);
+    let scope = (!inferred.scopes.is_empty() && inferred.unscoped.is_empty())
+        .then(|| inferred.scopes.join(","));
+
+    let trailers: Vec<String> = branch_info
+        .ticket
+        .iter()
+        .map(|ticket| format!("{}: {ticket}", config.branch.ticket_trailer))
+        .collect();
+
+    prepare_commit_msg(
+        buffer,
+        project_root,
+        &commit_type,
+        scope.as_deref(),
+        &branch_info.slug,
+        &trailers,
+        config.draft.grouping,
+        false,
+    );
+
+    annotate_draft_file(buffer, &config.guards);
+
+    let draft = utils::read_file(buffer);
+    if let Err(e) = std::fs::write(buffer, format!("{draft}{git_comments}")) {
+        eprintln!("Couldn't write to file: {e}");
+    }
+}
+
+///
+/// # `annotate_draft_file`
+/// Pre-fills the file bullets of the draft with the change hints, then writes the
+/// changes of each file and the findings of the guards under them.
+///
+/// ## Arguments
+/// * `path` - `&Path` - The commit message file
+/// * `config` - `&config::GuardsConfig` - The `[guards]` configuration
+fn annotate_draft_file(path: &Path, config: &config::GuardsConfig) {
+    // The hints fill the placeholders, then each annotation goes right under the bullet,
+    // the last one written comes first
+    let draft = symbols::fill_placeholders(&utils::read_file(path), &symbols::staged_hints());
+    let draft = guards::annotate_draft(&draft, &guards::scan_staged(config));
+    let draft = diffstat::annotate_staged_stats(&draft);
+
+    if let Err(e) = std::fs::write(path, draft) {
+        eprintln!("Couldn't write to file: {e}");
+    }
+}
+
+///
+/// # `sync_branch_draft`
+/// Puts the draft of the previous branch aside and brings back the draft of the
+/// current branch, when the branch changed since the draft was written.
+///
+/// ## Arguments
+/// * `draft_path` - `&Path` - The commit message file
+fn sync_branch_draft(draft_path: &Path) {
+    let branch = get_current_branch();
+    // A detached HEAD (rebase, bisect) keeps the current draft
+    if branch.is_empty() || branch == "HEAD" {
+        return;
+    }
+
+    let Some(git_dir) = git_related::get_git_dir() else {
+        return;
+    };
+
+    match drafts::sync_draft(&drafts::drafts_dir(&git_dir), draft_path, &branch) {
+        Ok(Some(swap)) => {
+            let restored = if swap.restored {
+                format!("the draft of `{branch}` is back")
+            } else {
+                format!("`{branch}` has no draft yet")
+            };
+
+            println!(
+                "{} the draft of `{}` was put aside, {restored}.",
+                Yellow.bold().paint("Drafts:"),
+                swap.previous
+            );
+
+            if let Some(kept) = swap.kept {
+                println!(
+                    "{} `{}` already had a draft put aside, it was kept as `{kept}`.",
+                    Yellow.bold().paint("Drafts:"),
+                    swap.previous
+                );
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("{} {e}", Red.bold().paint("Couldn't switch the drafts:")),
+    }
+}
+
 ///
 /// # `print_commit_message`
 /// Prints the commit message.
@@ -322,27 +1189,199 @@ fn main() {
 
     let verbose = cli.verbose;
 
+    let mut config = config::load_config(&project_root.join(CONFIG_FILE_PATH));
+    drop_unselectable_rules(&mut config.classifier.rules);
+
+    // The draft follows the branch, even after a plain `git switch`
+    if matches!(
+        cli.command,
+        Commands::Commit { .. }
+            | Commands::Generate { .. }
+            | Commands::Preview
+            | Commands::Continue
+            | Commands::Drafts { .. }
+    ) {
+        sync_branch_draft(commit_message_file_path);
+    }
+
     match &cli.command {
         Commands::AddAndExclude { exclude } => {
             let (successful_add, successfully_exclude) = add_with_exclude(exclude, verbose);
 
             println!("Added {successful_add} files to the commit and excluded {successfully_exclude} files.");
         }
-        Commands::Commit { push, args } => {
-            if commit_message_file_path.exists() {
+        Commands::Commit {
+            push,
+            args,
+            pair,
+            amend,
+            fixup,
+            squash,
+            from_draft,
+            skip_checks,
+            allow_secrets,
+            skip_guards,
+            flags,
+        } => {
+            block_on_conflicts();
+
+            if *amend {
+                if let Err(e) = amend::write_amend_draft(commit_message_file_path) {
+                    eprintln!("{} {e}", Red.bold().paint("Amend failed:"));
+                    std::process::exit(1);
+                }
+
+                utils::open_in_editor(commit_message_file_path);
+            }
+
+            if commit_message_file_path.exists() || *fixup {
                 // Read the file
-                let commit_message = utils::read_file(commit_message_file_path);
+                let draft = if commit_message_file_path.exists() {
+                    utils::read_file(commit_message_file_path)
+                } else {
+                    String::new()
+                };
+
+                let sections = split::split_sections(&draft);
+                if sections.len() > 1 {
+                    if *amend || *fixup || *squash {
+                        eprintln!(
+                            "{}",
+                            Red.bold().paint(
+                                "A split draft can't be used with --amend, --fixup or --squash."
+                            )
+                        );
+                        std::process::exit(1);
+                    }
+
+                    let co_authors = if *pair {
+                        pair::choose_co_authors(&config.pair.roster, &project_root)
+                    } else {
+                        Vec::new()
+                    };
+
+                    for section in &sections {
+                        warn_missing_ticket(&config.branch, section);
+                        warn_undescribed_files(&config.render, section);
+                    }
+
+                    let files = split::described_files(&draft);
+                    let existing_files: Vec<String> = files
+                        .iter()
+                        .filter(|file| project_root.join(file).exists())
+                        .cloned()
+                        .collect();
+                    if !secrets::check_secrets(
+                        &config.secrets,
+                        &project_root,
+                        &git_related::read_worktree_diff(&existing_files),
+                        &existing_files,
+                        *allow_secrets,
+                    ) {
+                        std::process::exit(1);
+                    }
+
+                    if !*skip_checks {
+                        run_checks_or_exit(&config, "commit", &files, &project_root, verbose);
+                    }
+
+                    let commit_options = flags.to_options(&config.commit);
+                    if let Err(e) = split::commit_sections(
+                        &sections,
+                        &co_authors,
+                        &config,
+                        &commit_options,
+                        !*skip_guards,
+                        verbose,
+                    ) {
+                        eprintln!("{} {e}", Red.bold().paint("Split commit failed:"));
+                        std::process::exit(1);
+                    }
+
+                    if *push {
+                        push_or_exit(args, &config, &project_root, *skip_checks, verbose);
+                    }
+
+                    return;
+                }
+
+                if *from_draft {
+                    if let Err(e) = split::stage_draft_files(&draft, verbose) {
+                        eprintln!("{} {e}", Red.bold().paint("Staging failed:"));
+                        std::process::exit(1);
+                    }
+                }
+
+                if !secrets::check_secrets(
+                    &config.secrets,
+                    &project_root,
+                    &git_related::read_added_diff(&["--cached"]),
+                    &git_related::get_diff_files(&["--cached", "--diff-filter=d"]),
+                    *allow_secrets,
+                ) {
+                    std::process::exit(1);
+                }
+
+                if !*skip_guards
+                    && !guards::report_findings(
+                        &guards::scan_staged(&config.guards),
+                        &config.guards,
+                    )
+                {
+                    std::process::exit(1);
+                }
+
+                if !*skip_checks {
+                    let files = git_related::get_diff_files(&["--cached"]);
+                    run_checks_or_exit(&config, "commit", &files, &project_root, verbose);
+                }
+
+                let mut commit_message = render::render_message(&draft, &config.render);
+
+                if *fixup || *squash {
+                    let base_branch = config
+                        .branch
+                        .base
+                        .clone()
+                        .unwrap_or_else(git_related::get_default_branch);
+
+                    let Some(target) = amend::pick_commit(&base_branch, "Commit to fix up") else {
+                        eprintln!("{}", Red.bold().paint("No commit to fix up."));
+                        std::process::exit(1);
+                    };
+
+                    commit_message = amend::fixup_message(&target, *squash, &commit_message);
+                }
+
+                if *pair {
+                    let co_authors = pair::choose_co_authors(&config.pair.roster, &project_root);
+                    commit_message = draft::append_trailers(&commit_message, &co_authors);
+                }
 
                 if verbose {
                     print_commit_message(&commit_message.clone());
                 }
 
+                warn_missing_ticket(&config.branch, &commit_message);
+                warn_undescribed_files(&config.render, &draft);
+
                 // Commit the changes
-                let succesfull_commit =
-                    commit(&commit_message, verbose).expect("Error commiting the changes");
+                let mut commit_options = flags.to_options(&config.commit);
+                commit_options.amend = *amend;
+                let Ok(succesfull_commit) = commit(&commit_message, &commit_options, verbose)
+                else {
+                    std::process::exit(1);
+                };
+
+                if commit_options.gpg_sign.is_some()
+                    || git_related::get_config_value("commit.gpgsign").as_deref() == Some("true")
+                    || verbose
+                {
+                    println!("Signature: {}", git_related::get_signature_status());
+                }
 
                 if *push && succesfull_commit {
-                    git_related::push(args.clone(), verbose).expect("Error pushing the changes");
+                    push_or_exit(args, &config, &project_root, *skip_checks, verbose);
                 }
             } else {
                 // Crash the program
@@ -354,27 +1393,242 @@ fn main() {
             }
         }
 
-        Commands::Generate => {
+        Commands::Generate { pair, split, group } => {
+            let grouping = group.unwrap_or(config.draft.grouping);
+
             create_needed_files(&project_root, verbose);
 
+            let git_dir = git_related::get_git_dir().expect("Not in a git repository");
+            if let Some(operation) = operation::detect_operation(&git_dir) {
+                println!(
+                    "{} {} in progress.",
+                    Yellow.bold().paint("Warning:"),
+                    operation.command()
+                );
+
+                let operation_message =
+                    std::fs::read_to_string(operation.message_file(&git_dir)).unwrap_or_default();
+
+                let mut conflicted_files = git_related::get_conflicted_files(&read_git_status());
+                for file in operation::parse_conflicts_comment(&operation_message) {
+                    if !conflicted_files.contains(&file) {
+                        conflicted_files.push(file);
+                    }
+                }
+
+                let draft = operation::build_operation_draft(
+                    &operation_message,
+                    &conflicted_files,
+                    get_current_commit_nb() + 1,
+                    &current_branch_info(&config.branch).slug,
+                );
+
+                if let Err(e) = std::fs::write(commit_message_file_path, draft) {
+                    eprintln!("Couldn't write to file: {e}");
+                }
+
+                utils::open_in_editor(commit_message_file_path);
+                return;
+            }
+
+            if *split {
+                let branch_info = current_branch_info(&config.branch);
+
+                let mut trailers: Vec<String> = branch_info
+                    .ticket
+                    .iter()
+                    .map(|ticket| format!("{}: {ticket}", config.branch.ticket_trailer))
+                    .collect();
+
+                if *pair {
+                    trailers.extend(pair::choose_co_authors(&config.pair.roster, &project_root));
+                }
+
+                prepare_split_commit_msg(
+                    commit_message_file_path,
+                    &config,
+                    &branch_info,
+                    &trailers,
+                    grouping,
+                    verbose,
+                );
+                annotate_draft_file(commit_message_file_path, &config.guards);
+
+                utils::open_in_editor(commit_message_file_path);
+                return;
+            }
+
+            // Preselect the commit type suggested by the changed paths
+            let suggestion = classify::suggest_commit_type(
+                &read_git_status(),
+                &git_related::read_git_diff(),
+                &config.classifier.rules,
+            );
+            let branch_info = current_branch_info(&config.branch);
+
+            // The branch type prefix is used when no rule matched
+            let position = |suggested_type: &str| {
+                COMMIT_TYPES
+                    .iter()
+                    .position(|commit_type| *commit_type == suggested_type)
+            };
+            let default_type = suggestion
+                .as_ref()
+                .and_then(|suggestion| position(&suggestion.commit_type))
+                .or_else(|| branch_info.kind.as_deref().and_then(position))
+                .unwrap_or(0);
+
+            if let Some(suggestion) = &suggestion {
+                println!(
+                    "Suggested: {} ({})",
+                    Green.bold().paint(&suggestion.commit_type),
+                    suggestion.reason
+                );
+            }
+
             let commit_type = COMMIT_TYPES[Select::with_theme(&my_theme::ColorfulTheme::default())
-                .default(0)
+                .default(default_type)
                 .items(&COMMIT_TYPES)
                 .interact()
                 .unwrap()];
 
-            prepare_commit_msg(commit_message_file_path, commit_type, verbose);
+            let scope = choose_scope(&project_root, &config.scopes);
 
-            // Open the file in Zed.
-            let _ = std::process::Command::new("zed")
-                .arg(commit_message_file_path)
-                .spawn()
-                .expect("Error opening the file in Zed")
-                .wait();
+            let mut trailers: Vec<String> = branch_info
+                .ticket
+                .iter()
+                .map(|ticket| format!("{}: {ticket}", config.branch.ticket_trailer))
+                .collect();
+
+            if *pair {
+                trailers.extend(pair::choose_co_authors(&config.pair.roster, &project_root));
+            }
+
+            prepare_commit_msg(
+                commit_message_file_path,
+                &project_root,
+                commit_type,
+                scope.as_deref(),
+                &branch_info.slug,
+                &trailers,
+                grouping,
+                verbose,
+            );
+            annotate_draft_file(commit_message_file_path, &config.guards);
+
+            utils::open_in_editor(commit_message_file_path);
         }
 
-        Commands::Push { args } => {
-            push(args.clone(), verbose).expect("Error pushing the changes");
+        Commands::Preview => {
+            if !commit_message_file_path.exists() {
+                eprintln!("{} {}", COMMIT_MESSAGE_FILE, Red.bold().paint("not found."));
+                std::process::exit(1);
+            }
+
+            let commit_message =
+                render::render_message(&utils::read_file(commit_message_file_path), &config.render);
+
+            print_commit_message(&commit_message);
+
+            for problem in render::check_subject(&commit_message, config.render.wrap_width) {
+                println!("{} {problem}", Yellow.bold().paint("Warning:"));
+            }
+        }
+
+        Commands::Continue => {
+            let git_dir = git_related::get_git_dir().expect("Not in a git repository");
+            let Some(operation) = operation::detect_operation(&git_dir) else {
+                eprintln!(
+                    "{}",
+                    Red.bold()
+                        .paint("No merge, rebase, cherry-pick or revert in progress.")
+                );
+                std::process::exit(1);
+            };
+
+            block_on_conflicts();
+
+            // The draft replaces the message prepared by git
+            let draft = std::fs::read_to_string(commit_message_file_path).unwrap_or_default();
+            if !draft.trim().is_empty() {
+                let commit_message = render::render_message(&draft, &config.render);
+
+                if let Err(e) = std::fs::write(operation.message_file(&git_dir), commit_message) {
+                    eprintln!("Couldn't write the message: {e}");
+                }
+            }
+
+            match git_related::run_sequencer_action(operation.command(), "--continue", verbose) {
+                Ok(()) => {
+                    // The draft belongs to this step, the next one needs a new draft
+                    let _ = std::fs::write(commit_message_file_path, "");
+
+                    println!(
+                        "{}",
+                        Green
+                            .bold()
+                            .paint(format!("{} continued.", operation.command()))
+                    );
+
+                    if let Some(operation) = operation::detect_operation(&git_dir) {
+                        println!("{} still in progress.", operation.command());
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{} {e}", Red.bold().paint("Continue failed:"));
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Abort => {
+            let git_dir = git_related::get_git_dir().expect("Not in a git repository");
+            let Some(operation) = operation::detect_operation(&git_dir) else {
+                eprintln!(
+                    "{}",
+                    Red.bold()
+                        .paint("No merge, rebase, cherry-pick or revert in progress.")
+                );
+                std::process::exit(1);
+            };
+
+            if Confirm::with_theme(&my_theme::ColorfulTheme::default())
+                .with_prompt(format!("Abort the {} ?", operation.command()))
+                .default(false)
+                .interact()
+                .unwrap()
+            {
+                if let Err(e) =
+                    git_related::run_sequencer_action(operation.command(), "--abort", verbose)
+                {
+                    eprintln!("{} {e}", Red.bold().paint("Abort failed:"));
+                    std::process::exit(1);
+                }
+
+                println!(
+                    "{}",
+                    Green
+                        .bold()
+                        .paint(format!("{} aborted.", operation.command()))
+                );
+            }
+        }
+
+        Commands::Autosquash => {
+            let base_branch = config
+                .branch
+                .base
+                .clone()
+                .unwrap_or_else(git_related::get_default_branch);
+
+            if let Err(e) = amend::autosquash(&base_branch, verbose) {
+                eprintln!("{} {e}", Red.bold().paint("Autosquash failed:"));
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Push { args, skip_checks } => {
+            push_or_exit(args, &config, &project_root, *skip_checks, verbose);
         }
 
         Commands::Switch { stash, apply_stash } => {
@@ -403,15 +1657,184 @@ fn main() {
                 .unwrap()
             {
                 switch_branch(chosen_branch.to_string());
+                sync_branch_draft(commit_message_file_path);
 
                 if *apply_stash {
                     stash_and_maybe_pop(true);
+
+                    offer_to_resolve_stash_conflicts(&project_root);
                 }
             } else {
                 println!("Bye !");
             }
         }
 
+        Commands::Hooks { action } => {
+            let Some(hooks_dir) = git_related::get_hooks_dir() else {
+                eprintln!("{}", Red.bold().paint("Not in a git repository."));
+                std::process::exit(1);
+            };
+
+            match action {
+                HooksCommands::Install => {
+                    let program = std::env::current_exe().unwrap();
+
+                    match hooks::install_hooks(&hooks_dir, &program) {
+                        Ok(chained) => {
+                            println!(
+                                "{} in `{}`.",
+                                Green.bold().paint("Hooks installed"),
+                                hooks_dir.display()
+                            );
+
+                            for name in chained {
+                                println!("The existing `{name}` hook is chained and runs first.");
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("{} {e}", Red.bold().paint("Install failed:"));
+                            std::process::exit(1);
+                        }
+                    }
+                }
+                HooksCommands::Uninstall => match hooks::uninstall_hooks(&hooks_dir) {
+                    Ok(removed) => println!("Removed {removed} hook(s)."),
+                    Err(e) => {
+                        eprintln!("{} {e}", Red.bold().paint("Uninstall failed:"));
+                        std::process::exit(1);
+                    }
+                },
+                HooksCommands::Status => {
+                    println!("Hooks folder: {}", hooks_dir.display());
+
+                    for name in hooks::HOOK_NAMES {
+                        let state = match hooks::hook_state(&hooks_dir, name) {
+                            hooks::HookState::Missing => Yellow.paint("not installed"),
+                            hooks::HookState::Installed { chained: false } => {
+                                Green.paint("installed")
+                            }
+                            hooks::HookState::Installed { chained: true } => {
+                                Green.paint("installed, chained")
+                            }
+                            hooks::HookState::Foreign => Red.paint("another hook is installed"),
+                        };
+
+                        println!("{name}: {state}");
+                    }
+                }
+            }
+        }
+
+        Commands::Drafts { action } => {
+            let drafts_dir =
+                drafts::drafts_dir(&git_related::get_git_dir().expect("Not in a git repository"));
+            let current_branch = get_current_branch();
+
+            match action {
+                DraftsCommands::List => {
+                    let mut found = false;
+
+                    if commit_message_file_path.exists() {
+                        let draft = utils::read_file(commit_message_file_path);
+                        if !draft.trim().is_empty() {
+                            found = true;
+                            println!(
+                                "{} {current_branch}  {}",
+                                Green.bold().paint("*"),
+                                drafts::draft_title(&draft)
+                            );
+                        }
+                    }
+
+                    for branch in drafts::list_stored_drafts(&drafts_dir) {
+                        found = true;
+                        let draft =
+                            utils::read_file(&drafts::stored_draft_path(&drafts_dir, &branch));
+                        println!("  {branch}  {}", drafts::draft_title(&draft));
+                    }
+
+                    if !found {
+                        println!("No draft.");
+                    }
+                }
+                DraftsCommands::Show { branch } | DraftsCommands::Delete { branch } => {
+                    let branch = branch.clone().unwrap_or(current_branch.clone());
+                    let path = if branch == current_branch {
+                        commit_message_file_path.to_path_buf()
+                    } else {
+                        drafts::stored_draft_path(&drafts_dir, &branch)
+                    };
+
+                    if !path.exists() {
+                        eprintln!("No draft for `{branch}`.");
+                        std::process::exit(1);
+                    }
+
+                    if matches!(action, DraftsCommands::Show { .. }) {
+                        print!("{}", utils::read_file(&path));
+                    } else if Confirm::with_theme(&my_theme::ColorfulTheme::default())
+                        .with_prompt(format!("Delete the draft of `{branch}` ?"))
+                        .default(false)
+                        .interact()
+                        .unwrap()
+                    {
+                        if let Err(e) = std::fs::remove_file(&path) {
+                            eprintln!("{} {e}", Red.bold().paint("Couldn't delete the draft:"));
+                            std::process::exit(1);
+                        }
+
+                        println!("{}", Green.bold().paint("Draft deleted."));
+                    }
+                }
+            }
+        }
+
+        Commands::Hook { name, args } => {
+            match (name.as_str(), args.as_slice()) {
+                // Only a plain `git commit`, not `-m`, `-F`, a template, a merge or an amend
+                ("prepare-commit-msg", [buffer]) => {
+                    fill_commit_buffer(Path::new(buffer), &project_root, &config);
+                }
+                ("prepare-commit-msg", _) => {}
+                ("commit-msg", [buffer, ..]) => {
+                    let buffer = Path::new(buffer);
+                    // With `git commit -v`, the diff below the scissors line isn't part of the message
+                    let buffer_content = utils::read_file(buffer);
+                    let draft = hooks::cut_at_scissors(&buffer_content);
+                    warn_undescribed_files(&config.render, draft);
+                    let message = render::render_message(draft, &config.render);
+
+                    let Ok(header_rule) = regex::Regex::new(&config.format.header_pattern) else {
+                        eprintln!(
+                            "{} {}",
+                            Red.bold().paint("Invalid header pattern:"),
+                            config.format.header_pattern
+                        );
+                        std::process::exit(1);
+                    };
+
+                    let problems =
+                        hooks::lint_message(&message, &header_rule, config.render.wrap_width);
+                    if !problems.is_empty() {
+                        for problem in problems {
+                            eprintln!("{} {problem}", Red.bold().paint("Error:"));
+                        }
+                        eprintln!("The message was not committed, `git commit --no-verify` skips the checks.");
+                        std::process::exit(1);
+                    }
+
+                    if let Err(e) = std::fs::write(buffer, message) {
+                        eprintln!("Couldn't write to file: {e}");
+                        std::process::exit(1);
+                    }
+                }
+                _ => {
+                    eprintln!("{} {name}", Red.bold().paint("Unknown hook:"));
+                    std::process::exit(1);
+                }
+            }
+        }
+
         Commands::ListStatus => {
             let files = git_related::get_status_files();
             // Print each file on a new line for fish shell completion
@@ -419,5 +1842,137 @@ fn main() {
                 println!("{file}");
             }
         }
+
+        Commands::Stash { action } => {
+            let run = |args: Vec<String>| {
+                if let Err(e) = git_related::run_stash_command(&args, verbose) {
+                    eprintln!("{} {e}", Red.bold().paint("Stash failed:"));
+                }
+            };
+
+            match action {
+                None | Some(StashCommands::List) => stash::print_stash_list(),
+                Some(StashCommands::Push { message, paths }) => {
+                    let mut args = vec!["push".to_string(), "-u".to_string()];
+                    if let Some(message) = message {
+                        args.extend(["-m".to_string(), message.clone()]);
+                    }
+                    if !paths.is_empty() {
+                        args.push("--".to_string());
+                        args.extend(paths.iter().cloned());
+                    }
+
+                    run(args);
+                }
+                Some(action) => {
+                    let Some(reference) = stash::pick_stash("Stash entry") else {
+                        println!("No stash entry.");
+                        return;
+                    };
+
+                    match action {
+                        StashCommands::Show => git_related::show_stash_diff(&reference),
+                        StashCommands::Apply | StashCommands::Pop => {
+                            let command = if matches!(action, StashCommands::Pop) {
+                                "pop"
+                            } else {
+                                "apply"
+                            };
+
+                            run(vec![command.to_string(), reference]);
+                            offer_to_resolve_stash_conflicts(&project_root);
+                        }
+                        StashCommands::Drop => {
+                            if Confirm::with_theme(&my_theme::ColorfulTheme::default())
+                                .with_prompt(format!("Drop {reference} ?"))
+                                .default(false)
+                                .interact()
+                                .unwrap()
+                            {
+                                run(vec!["drop".to_string(), reference]);
+                            }
+                        }
+                        StashCommands::Branch { name } => {
+                            run(vec!["branch".to_string(), name.clone(), reference]);
+                        }
+                        StashCommands::List | StashCommands::Push { .. } => unreachable!(),
+                    }
+                }
+            }
+        }
+
+        Commands::Resolve => {
+            let remaining = resolve::resolve_conflicts(&project_root);
+
+            if remaining > 0 {
+                println!("{remaining} conflicted file(s) left.");
+            }
+        }
+
+        Commands::Release {
+            dry_run,
+            push,
+            args,
+        } => {
+            let push_args = if *push {
+                Some(args.clone().unwrap_or_default())
+            } else {
+                None
+            };
+
+            let commit_options = CommitFlags::default().to_options(&config.commit);
+
+            if let Err(e) = release::release(
+                &project_root,
+                &config.release,
+                *dry_run,
+                push_args,
+                &config.push,
+                &commit_options,
+                verbose,
+            ) {
+                eprintln!("{} {e}", Red.bold().paint("Release failed:"));
+                std::process::exit(1);
+            }
+        }
+
+        Commands::History { path, grep } => {
+            let absolute_path = caller.join(path);
+            let relative_path = absolute_path
+                .strip_prefix(&project_root)
+                .unwrap_or(Path::new(path));
+
+            let relative_path = if relative_path.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                relative_path
+            };
+
+            history::history(
+                &relative_path.display().to_string(),
+                absolute_path.is_dir(),
+                grep.as_deref(),
+            );
+        }
+
+        Commands::Stats { range, json, top } => {
+            let Ok(header_rule) = regex::Regex::new(&config.format.header_pattern) else {
+                eprintln!(
+                    "{} {}",
+                    Red.bold().paint("Invalid header pattern:"),
+                    config.format.header_pattern
+                );
+                std::process::exit(1);
+            };
+
+            let commits = git_related::get_log_entries(&[range.as_deref().unwrap_or("HEAD")]);
+            let stats = stats::compute_stats(&commits, &header_rule, *top);
+
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&stats).unwrap());
+            } else {
+                stats::print_stats(&stats);
+            }
+        }
     }
 }
@@ -0,0 +1,192 @@
+///
+/// # `operation.rs`
+/// Contains the functions used while a merge, a rebase, a cherry-pick or a revert
+/// is in progress.
+///
+/// The operation is detected from the files git leaves in its directory.
+// Imports ================================================================================= Imports
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::draft::{parse_file_descriptions, parse_header};
+
+// Structs  ===============================================================================  Structs
+/// An operation in progress.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+}
+
+impl Operation {
+    ///
+    /// # `command`
+    /// Returns the git command of the operation.
+    ///
+    /// ## Returns
+    /// * `&str` - The git command
+    pub fn command(self) -> &'static str {
+        match self {
+            Operation::Merge => "merge",
+            Operation::Rebase => "rebase",
+            Operation::CherryPick => "cherry-pick",
+            Operation::Revert => "revert",
+        }
+    }
+
+    ///
+    /// # `message_file`
+    /// Returns the file git reads the message of the next commit from.
+    ///
+    /// ## Arguments
+    /// * `git_dir` - `&Path` - The git directory
+    ///
+    /// ## Returns
+    /// * `PathBuf` - The message file
+    pub fn message_file(self, git_dir: &Path) -> PathBuf {
+        match self {
+            Operation::Rebase if git_dir.join("rebase-merge").is_dir() => {
+                git_dir.join("rebase-merge/message")
+            }
+            Operation::Rebase => git_dir.join("rebase-apply/msg"),
+            _ => git_dir.join("MERGE_MSG"),
+        }
+    }
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `detect_operation`
+/// Detects the operation in progress from the git directory.
+///
+/// ## Arguments
+/// * `git_dir` - `&Path` - The git directory
+///
+/// ## Returns
+/// * `Option<Operation>` - The operation, `None` if there is none
+pub fn detect_operation(git_dir: &Path) -> Option<Operation> {
+    if git_dir.join("rebase-merge").is_dir() || git_dir.join("rebase-apply").is_dir() {
+        Some(Operation::Rebase)
+    } else if git_dir.join("CHERRY_PICK_HEAD").exists() {
+        Some(Operation::CherryPick)
+    } else if git_dir.join("REVERT_HEAD").exists() {
+        Some(Operation::Revert)
+    } else if git_dir.join("MERGE_HEAD").exists() {
+        Some(Operation::Merge)
+    } else {
+        None
+    }
+}
+
+///
+/// # `parse_conflicts_comment`
+/// Parses the `# Conflicts:` comment git writes in `MERGE_MSG`.
+///
+/// ## Arguments
+/// * `message` - `&str` - The message
+///
+/// ## Returns
+/// * `Vec<String>` - The files that were in conflict
+pub fn parse_conflicts_comment(message: &str) -> Vec<String> {
+    let file_rule = Regex::new(r"^#\s+(\S.*)$").unwrap();
+
+    message
+        .lines()
+        .skip_while(|line| line.trim() != "# Conflicts:")
+        .skip(1)
+        .take_while(|line| line.starts_with('#') && line.trim() != "#")
+        .filter_map(|line| file_rule.captures(line))
+        .map(|captures| captures[1].trim().to_string())
+        .collect()
+}
+
+///
+/// # `build_operation_draft`
+/// Builds the draft of the commit concluding an operation: the operation message,
+/// followed by the conflicted files with a place for the resolution notes.
+///
+/// ## Arguments
+/// * `operation_message` - `&str` - The message prepared by git
+/// * `conflicted_files` - `&[String]` - The files in conflict
+/// * `commit_number` - `u16` - The commit number written in the header
+/// * `branch_name` - `&str` - The branch name written in the header
+///
+/// ## Returns
+/// * `String` - The draft
+pub fn build_operation_draft(
+    operation_message: &str,
+    conflicted_files: &[String],
+    commit_number: u16,
+    branch_name: &str,
+) -> String {
+    let mut lines = operation_message
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .skip_while(|line| line.trim().is_empty());
+
+    let subject = lines.next().unwrap_or_default().trim();
+    let body: String = lines.collect::<Vec<&str>>().join("\n");
+
+    // A cherry-picked commit already has a header
+    let mut draft = if parse_header(subject).is_some() {
+        format!("{subject}\n\n")
+    } else {
+        format!("[{commit_number}] (chore on {branch_name}) {subject}\n\n")
+    };
+
+    if !body.trim().is_empty() {
+        draft.push_str(body.trim());
+        draft.push_str("\n\n");
+    }
+
+    let described: Vec<String> = parse_file_descriptions(&body)
+        .into_iter()
+        .map(|description| description.path)
+        .collect();
+
+    for file in conflicted_files {
+        if !described.contains(file) {
+            draft.push_str(&format!("- `{file}`: conflict resolved\n\n\t\n\n"));
+        }
+    }
+
+    draft
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::{build_operation_draft, parse_conflicts_comment};
+
+    #[test]
+    fn test_parse_conflicts_comment() {
+        let message = "Merge branch 'login'\n\n# Conflicts:\n#\tsrc/main.rs\n#\tREADME.md\n#\n# It looks like you may be committing a merge.\n";
+
+        assert_eq!(
+            parse_conflicts_comment(message),
+            vec!["src/main.rs", "README.md"]
+        );
+    }
+
+    #[test]
+    fn test_build_operation_draft() {
+        let conflicted = vec!["src/main.rs".to_string()];
+
+        assert_eq!(
+            build_operation_draft("Merge branch 'login'\n\n# Conflicts:\n#\tsrc/main.rs\n", &conflicted, 4, "main"),
+            "[4] (chore on main) Merge branch 'login'\n\n- `src/main.rs`: conflict resolved\n\n\t\n\n"
+        );
+        assert_eq!(
+            build_operation_draft(
+                "[2] (feat on login) Add the form\n\n- `src/main.rs`:\n\n\tForm\n",
+                &conflicted,
+                4,
+                "main"
+            ),
+            "[2] (feat on login) Add the form\n\n- `src/main.rs`:\n\n\tForm\n\n"
+        );
+    }
+}
@@ -0,0 +1,196 @@
+///
+/// # `pair.rs`
+/// Contains the functions used to add `Co-authored-by:` trailers.
+///
+/// The roster comes from the `[pair]` configuration, or from the `.mailmap`
+/// and the recent committers. The last chosen pair is remembered per repository.
+// Imports ================================================================================= Imports
+use std::path::Path;
+
+use dialoguer::MultiSelect;
+use regex::Regex;
+
+use crate::git_related::{get_config_value, get_git_dir, get_recent_authors};
+use crate::my_theme::ColorfulTheme;
+
+// Constants  ===========================================================================  Constants
+/// File storing the last chosen pair, inside the git directory
+const RECENT_PAIRS_FILE: &str = "git-commands/pairs";
+/// Number of commits looked at to find the recent committers
+const RECENT_COMMITS: usize = 200;
+
+// Functions  ===========================================================================  Functions
+///
+/// # `parse_mailmap`
+/// Parses the proper names and emails of a `.mailmap` file.
+///
+/// ## Arguments
+/// * `content` - `&str` - The `.mailmap` content
+///
+/// ## Returns
+/// * `Vec<String>` - The people (`Name <email>`)
+pub fn parse_mailmap(content: &str) -> Vec<String> {
+    let regex_rule = Regex::new(r"^\s*([^<#]*?)\s*<([^>]+)>").unwrap();
+
+    content
+        .lines()
+        .filter_map(|line| regex_rule.captures(line))
+        .filter(|captures| !captures[1].is_empty())
+        .map(|captures| format!("{} <{}>", &captures[1], &captures[2]))
+        .collect()
+}
+
+///
+/// # `email_of`
+/// Returns the email of a `Name <email>` entry.
+///
+/// ## Arguments
+/// * `person` - `&str` - The entry
+///
+/// ## Returns
+/// * `String` - The lowercase email, the whole entry if it has no email
+fn email_of(person: &str) -> String {
+    person
+        .rsplit_once('<')
+        .map_or(person, |(_, email)| email.trim_end_matches('>'))
+        .to_lowercase()
+}
+
+///
+/// # `build_roster`
+/// Builds the list of people to pair with, without the current user.
+///
+/// ## Arguments
+/// * `roster` - `&[String]` - The configured roster
+/// * `project_root` - `&Path` - The project root
+///
+/// ## Returns
+/// * `Vec<String>` - The people (`Name <email>`), without duplicated emails
+pub fn build_roster(roster: &[String], project_root: &Path) -> Vec<String> {
+    let candidates: Vec<String> = if roster.is_empty() {
+        let mailmap = std::fs::read_to_string(project_root.join(".mailmap")).unwrap_or_default();
+
+        let mut candidates = parse_mailmap(&mailmap);
+        candidates.extend(get_recent_authors(RECENT_COMMITS));
+
+        candidates
+    } else {
+        roster.to_vec()
+    };
+
+    let own_email = get_config_value("user.email").map(|email| email.to_lowercase());
+
+    let mut people: Vec<String> = Vec::new();
+    for candidate in candidates {
+        let email = email_of(&candidate);
+
+        if own_email.as_ref() != Some(&email)
+            && !people.iter().any(|person| email_of(person) == email)
+        {
+            people.push(candidate);
+        }
+    }
+
+    people
+}
+
+///
+/// # `read_recent_pairs`
+/// Reads the last chosen pair of the repository.
+///
+/// ## Returns
+/// * `Vec<String>` - The people (`Name <email>`)
+fn read_recent_pairs() -> Vec<String> {
+    get_git_dir()
+        .and_then(|git_dir| std::fs::read_to_string(git_dir.join(RECENT_PAIRS_FILE)).ok())
+        .map(|content| content.lines().map(ToString::to_string).collect())
+        .unwrap_or_default()
+}
+
+///
+/// # `save_recent_pairs`
+/// Remembers the chosen pair for the repository.
+///
+/// ## Arguments
+/// * `pairs` - `&[String]` - The people (`Name <email>`)
+fn save_recent_pairs(pairs: &[String]) {
+    let Some(path) = get_git_dir().map(|git_dir| git_dir.join(RECENT_PAIRS_FILE)) else {
+        return;
+    };
+
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+
+    if let Err(e) = std::fs::write(&path, pairs.join("\n")) {
+        eprintln!("Couldn't remember the pair: {e}");
+    }
+}
+
+///
+/// # `choose_co_authors`
+/// Lets the user pick the people they paired with.
+/// The last chosen pair is listed first and checked by default.
+///
+/// ## Arguments
+/// * `roster` - `&[String]` - The configured roster
+/// * `project_root` - `&Path` - The project root
+///
+/// ## Returns
+/// * `Vec<String>` - The `Co-authored-by:` trailers
+pub fn choose_co_authors(roster: &[String], project_root: &Path) -> Vec<String> {
+    let recent_pairs = read_recent_pairs();
+
+    let mut people: Vec<String> = recent_pairs.clone();
+    for person in build_roster(roster, project_root) {
+        if !people
+            .iter()
+            .any(|known| email_of(known) == email_of(&person))
+        {
+            people.push(person);
+        }
+    }
+
+    if people.is_empty() {
+        println!("Nobody to pair with: the roster is empty.");
+        return Vec::new();
+    }
+
+    let defaults: Vec<bool> = people
+        .iter()
+        .map(|person| recent_pairs.contains(person))
+        .collect();
+
+    let chosen: Vec<String> = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Pair with")
+        .items(&people)
+        .defaults(&defaults)
+        .interact()
+        .unwrap()
+        .into_iter()
+        .map(|index| people[index].clone())
+        .collect();
+
+    save_recent_pairs(&chosen);
+
+    chosen
+        .iter()
+        .map(|person| format!("Co-authored-by: {person}"))
+        .collect()
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::parse_mailmap;
+
+    #[test]
+    fn test_parse_mailmap() {
+        let mailmap = "# Team\nAna Silva <ana@mail.com> <ana@old.com>\n<tom@mail.com> <tom@old.com>\nTom P. <tom@mail.com>\n";
+
+        assert_eq!(
+            parse_mailmap(mailmap),
+            vec!["Ana Silva <ana@mail.com>", "Tom P. <tom@mail.com>"]
+        );
+    }
+}
@@ -0,0 +1,381 @@
+///
+/// # `push.rs`
+/// Contains the smart push: upstream detection, protected branches,
+/// `--force-with-lease` and the explanation of the rejected pushes.
+// Imports ================================================================================= Imports
+use std::time::Duration;
+
+use ansi_term::Colour::{Red, Yellow};
+use dialoguer::Confirm;
+
+use crate::classify::build_glob_set;
+use crate::config::PushConfig;
+use crate::git_related::{
+    count_commits, fetch_branch, get_current_branch, get_remote_branch_sha, get_upstream,
+    pull_rebase, push,
+};
+use crate::my_theme::ColorfulTheme;
+
+// Constants  ===========================================================================  Constants
+/// The `git push` options whose value is the next argument
+const OPTIONS_WITH_VALUE: [&str; 5] = ["-o", "--push-option", "--repo", "--receive-pack", "--exec"];
+
+// Structs  ===============================================================================  Structs
+/// Why a push was rejected.
+#[derive(Debug, PartialEq)]
+pub enum Rejection {
+    /// The remote branch has commits the local branch doesn't have
+    NonFastForward,
+    /// The remote branch moved since the last fetch, the lease doesn't hold
+    StaleLease,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `parse_rejection`
+/// Finds why git rejected a push.
+///
+/// ## Arguments
+/// * `stderr` - `&str` - The `git push` error output
+///
+/// ## Returns
+/// * `Option<Rejection>` - The reason, `None` if the push wasn't rejected
+pub fn parse_rejection(stderr: &str) -> Option<Rejection> {
+    if !stderr.contains("[rejected]") {
+        None
+    } else if stderr.contains("stale info") {
+        Some(Rejection::StaleLease)
+    } else if stderr.contains("fetch first") || stderr.contains("non-fast-forward") {
+        Some(Rejection::NonFastForward)
+    } else {
+        None
+    }
+}
+
+///
+/// # `takes_value`
+/// Checks if an option takes the next argument as its value.
+/// A short flags cluster ending with `o` (`-uo ci.skip`) does too.
+///
+/// ## Arguments
+/// * `arg` - `&str` - The option
+///
+/// ## Returns
+/// * `bool` - `true` if the next argument is its value
+fn takes_value(arg: &str) -> bool {
+    OPTIONS_WITH_VALUE.contains(&arg)
+        || (!arg.starts_with("--") && arg.find('o') == Some(arg.len() - 1))
+}
+
+///
+/// # `positional_args`
+/// Returns the arguments that aren't options: the remote and the refspecs.
+/// The values of the options (`-o ci.skip`, `--repo origin`) are skipped too.
+///
+/// ## Arguments
+/// * `args` - `&[String]` - The `git push` arguments
+///
+/// ## Returns
+/// * `Vec<&str>` - The positional arguments
+fn positional_args(args: &[String]) -> Vec<&str> {
+    let mut positional: Vec<&str> = Vec::new();
+    let mut args = args.iter().map(String::as_str);
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            positional.extend(args.by_ref());
+        } else if !arg.starts_with('-') {
+            positional.push(arg);
+        } else if takes_value(arg) {
+            args.next();
+        }
+    }
+
+    positional
+}
+
+///
+/// # `rewrite_force_args`
+/// Turns the force pushes into `--force-with-lease`: `--force`, `-f`, the `f` of a
+/// short flags cluster (`-fu`) and the `+` of the refspecs (`origin +main`).
+///
+/// ## Arguments
+/// * `args` - `&[String]` - The `git push` arguments
+/// * `lease` - `Option<&str>` - The expected value (`branch:sha`), `None` for the remote-tracking one
+///
+/// ## Returns
+/// * `Option<Vec<String>>` - The rewritten arguments, `None` if nothing was forced
+pub fn rewrite_force_args(args: &[String], lease: Option<&str>) -> Option<Vec<String>> {
+    let mut rewritten: Vec<String> = Vec::new();
+    let mut forced = false;
+    let mut forced_refspecs = 0;
+    let mut positional = 0;
+    let mut only_positional = false;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if only_positional || !arg.starts_with('-') {
+            // The first positional argument is the remote
+            positional += 1;
+            match arg.strip_prefix('+').filter(|_| positional > 1) {
+                Some(refspec) => {
+                    forced_refspecs += 1;
+                    rewritten.push(refspec.to_string());
+                }
+                None => rewritten.push(arg.clone()),
+            }
+        } else if arg == "--" {
+            only_positional = true;
+            rewritten.push(arg.clone());
+        } else if arg == "--force" {
+            forced = true;
+        } else if !arg.starts_with("--") && arg.contains('f') {
+            // The letters after `o` are its value
+            let (flags, value) = arg[1..].split_at(arg[1..].find('o').unwrap_or(arg.len() - 1));
+            let cluster = format!("{}{value}", flags.replace('f', ""));
+
+            forced |= flags.contains('f');
+            if !cluster.is_empty() {
+                rewritten.push(format!("-{cluster}"));
+            }
+            if takes_value(arg) {
+                rewritten.extend(args.next().cloned());
+            }
+        } else {
+            rewritten.push(arg.clone());
+            if takes_value(arg) {
+                rewritten.extend(args.next().cloned());
+            }
+        }
+    }
+
+    if !forced && forced_refspecs == 0 {
+        return None;
+    }
+
+    // The lease value only covers one branch
+    let lease_arg = match lease.filter(|_| forced_refspecs <= 1) {
+        Some(lease) => format!("--force-with-lease={lease}"),
+        None => "--force-with-lease".to_string(),
+    };
+    rewritten.insert(0, lease_arg);
+
+    Some(rewritten)
+}
+
+///
+/// # `smart_push`
+/// Pushes the current branch.
+/// Offers to set the upstream when the branch has none, asks for a confirmation
+/// before pushing to a protected branch, turns force pushes into `--force-with-lease`
+/// against the last fetched value, and explains the rejected pushes.
+///
+/// ## Arguments
+/// * `args` - `Option<Vec<String>>` - The `git push` arguments
+/// * `config` - `&PushConfig` - The push configuration
+/// * `verbose` - `bool` - Verbose the operation
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the push
+pub fn smart_push(
+    args: Option<Vec<String>>,
+    config: &PushConfig,
+    verbose: bool,
+) -> Result<(), String> {
+    let mut args = args.unwrap_or_default();
+    let current_branch = get_current_branch();
+    let upstream = get_upstream();
+
+    // The target is `git push <remote> <src>:<dst>`, then the upstream, then the current branch
+    let positional = positional_args(&args);
+    let upstream_parts = upstream
+        .as_deref()
+        .and_then(|upstream| upstream.split_once('/'));
+
+    let remote = positional
+        .first()
+        .map(ToString::to_string)
+        .or_else(|| upstream_parts.map(|(remote, _)| remote.to_string()))
+        .unwrap_or_else(|| config.remote.clone());
+    let branch = positional
+        .get(1)
+        .map(|refspec| {
+            let refspec = refspec.trim_start_matches('+');
+            refspec
+                .split_once(':')
+                .map_or(refspec, |(_, destination)| destination)
+                .to_string()
+        })
+        .or_else(|| upstream_parts.map(|(_, branch)| branch.to_string()))
+        .unwrap_or_else(|| current_branch.clone());
+
+    let sets_upstream = args
+        .iter()
+        .any(|arg| arg == "-u" || arg == "--set-upstream");
+    if upstream.is_none() && !sets_upstream && positional.is_empty() {
+        if Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "`{current_branch}` has no upstream, set it to `{remote}/{current_branch}` ?"
+            ))
+            .default(true)
+            .interact()
+            .unwrap()
+        {
+            args.extend([
+                "--set-upstream".to_string(),
+                remote.clone(),
+                current_branch.clone(),
+            ]);
+        } else {
+            return Err("No upstream to push to.".to_string());
+        }
+    }
+
+    if build_glob_set(&config.protected).is_match(&branch)
+        && !Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("`{branch}` is protected, push anyway ?"))
+            .default(false)
+            .interact()
+            .unwrap()
+    {
+        return Err(format!("Push to `{branch}` cancelled."));
+    }
+
+    let lease = get_remote_branch_sha(&remote, &branch).map(|sha| format!("{branch}:{sha}"));
+    if let Some(rewritten) = rewrite_force_args(&args, lease.as_deref()) {
+        println!(
+            "{} force push turned into --force-with-lease.",
+            Yellow.bold().paint("Note:")
+        );
+        args = rewritten;
+    }
+
+    let timeout = config.timeout.map(Duration::from_secs);
+    let Err(stderr) = push(Some(args), timeout, verbose) else {
+        return Ok(());
+    };
+
+    match parse_rejection(&stderr) {
+        Some(Rejection::NonFastForward) => {
+            fetch_branch(&remote, &branch);
+            let new_commits = count_commits(&format!("HEAD..{remote}/{branch}"));
+
+            if Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!(
+                    "Rejected: the remote has {new_commits} new commit(s), pull --rebase ?"
+                ))
+                .default(true)
+                .interact()
+                .unwrap()
+            {
+                pull_rebase(&remote, &branch)?;
+                println!("Rebased on `{remote}/{branch}`, push again.");
+            }
+        }
+        Some(Rejection::StaleLease) => {
+            println!(
+                "{} `{remote}/{branch}` moved since the last fetch, fetch and review it before forcing.",
+                Red.bold().paint("Rejected:")
+            );
+        }
+        None => eprintln!("Error: {stderr}"),
+    }
+
+    Err("Push failed.".to_string())
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::{parse_rejection, positional_args, rewrite_force_args, Rejection};
+
+    fn to_args(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_rejection() {
+        let fetch_first =
+            " ! [rejected]        main -> main (fetch first)\nerror: failed to push some refs";
+        let stale =
+            " ! [rejected]        main -> main (stale info)\nerror: failed to push some refs";
+
+        assert_eq!(
+            parse_rejection(fetch_first),
+            Some(Rejection::NonFastForward)
+        );
+        assert_eq!(parse_rejection(stale), Some(Rejection::StaleLease));
+        assert_eq!(parse_rejection("fatal: could not read from remote"), None);
+    }
+
+    #[test]
+    fn test_positional_args() {
+        assert_eq!(
+            positional_args(&to_args(&["-o", "ci.skip", "origin", "main"])),
+            vec!["origin", "main"]
+        );
+        assert_eq!(
+            positional_args(&to_args(&["--repo", "upstream", "--force-with-lease=main"])),
+            Vec::<&str>::new()
+        );
+        assert_eq!(
+            positional_args(&to_args(&["-u", "--", "origin", "-main"])),
+            vec!["origin", "-main"]
+        );
+        assert_eq!(
+            positional_args(&to_args(&["-uo", "ci.skip", "origin"])),
+            vec!["origin"]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_force_args() {
+        let args: Vec<String> = ["-f", "origin", "main"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            rewrite_force_args(&args, Some("main:1a2b3c")),
+            Some(vec![
+                "--force-with-lease=main:1a2b3c".to_string(),
+                "origin".to_string(),
+                "main".to_string()
+            ])
+        );
+        assert_eq!(rewrite_force_args(&args[1..], None), None);
+
+        assert_eq!(
+            rewrite_force_args(&to_args(&["-fu", "origin", "main"]), None),
+            Some(to_args(&["--force-with-lease", "-u", "origin", "main"]))
+        );
+        assert_eq!(
+            rewrite_force_args(&to_args(&["-uf", "-o", "+x", "origin"]), None),
+            Some(to_args(&["--force-with-lease", "-u", "-o", "+x", "origin"]))
+        );
+        assert_eq!(
+            rewrite_force_args(&to_args(&["origin", "+main"]), Some("main:1a2b3c")),
+            Some(to_args(&[
+                "--force-with-lease=main:1a2b3c",
+                "origin",
+                "main"
+            ]))
+        );
+        assert_eq!(
+            rewrite_force_args(
+                &to_args(&["origin", "+main", "+dev:dev"]),
+                Some("main:1a2b3c")
+            ),
+            Some(to_args(&[
+                "--force-with-lease",
+                "origin",
+                "main",
+                "dev:dev"
+            ]))
+        );
+        assert_eq!(
+            rewrite_force_args(&to_args(&["-ofast", "origin", "main"]), None),
+            None
+        );
+    }
+}
@@ -0,0 +1,504 @@
+///
+/// # `release.rs`
+/// Contains the functions used by the `release` subcommand.
+///
+/// The commits since the last tag are classified by type in order to
+/// propose the next semantic version:
+/// * breaking change → major
+/// * `feat` → minor
+/// * `fix` → patch
+// Imports ================================================================================= Imports
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use ansi_term::Colour::{Green, Yellow};
+use dialoguer::Confirm;
+use regex::Regex;
+use semver::Version;
+
+use crate::config::{PushConfig, ReleaseConfig};
+use crate::draft::parse_header;
+use crate::git_related::{
+    add_files, commit, create_annotated_tag, get_commit_messages, get_current_branch,
+    get_current_commit_nb, get_diff_files, get_last_tag, CommitOptions,
+};
+use crate::my_theme::ColorfulTheme;
+use crate::push::smart_push;
+
+// Constants  ===========================================================================  Constants
+/// The `version` line of a TOML manifest
+const TOML_VERSION_PATTERN: &str = r#"^(\s*version\s*=\s*")([^"]*)(".*)$"#;
+/// The first `"version": "..."` entry of the other manifests
+const JSON_VERSION_PATTERN: &str = r#"("version"\s*:\s*")([^"]*)(")"#;
+
+// Enums ===================================================================================== Enums
+/// The part of the version to bump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `classify_commit`
+/// Returns the version bump implied by a commit message.
+///
+/// ## Arguments
+/// * `message` - `&str` - The full commit message
+///
+/// ## Returns
+/// * `Option<Bump>` - The bump, `None` for the commits that don't need a release (`chore`, `test`, ...)
+pub fn classify_commit(message: &str) -> Option<Bump> {
+    let breaking_footer = message
+        .lines()
+        .any(|line| line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:"));
+
+    let header = parse_header(message.lines().next().unwrap_or_default());
+
+    if breaking_footer || header.as_ref().is_some_and(|header| header.breaking) {
+        return Some(Bump::Major);
+    }
+
+    match header?.commit_type.as_str() {
+        "feat" => Some(Bump::Minor),
+        "fix" => Some(Bump::Patch),
+        _ => None,
+    }
+}
+
+///
+/// # `next_version`
+/// Bumps a version.
+///
+/// ## Arguments
+/// * `current` - `&Version` - The current version
+/// * `bump` - `Bump` - The part to bump
+///
+/// ## Returns
+/// * `Version` - The next version
+pub fn next_version(current: &Version, bump: Bump) -> Version {
+    match bump {
+        Bump::Major => Version::new(current.major + 1, 0, 0),
+        Bump::Minor => Version::new(current.major, current.minor + 1, 0),
+        Bump::Patch => Version::new(current.major, current.minor, current.patch + 1),
+    }
+}
+
+///
+/// # `is_toml`
+/// Checks if a manifest is a TOML file.
+///
+/// ## Arguments
+/// * `file_name` - `&str` - The manifest file name
+///
+/// ## Returns
+/// * `bool` - `true` for a `.toml` file
+fn is_toml(file_name: &str) -> bool {
+    Path::new(file_name)
+        .extension()
+        .is_some_and(|extension| extension == "toml")
+}
+
+///
+/// # `package_version_line`
+/// Finds the `version` line of the `[package]` (or `[workspace.package]`) section of a TOML manifest.
+/// The `version` keys of the other sections (dependencies, ...) are skipped.
+///
+/// ## Arguments
+/// * `lines` - `&[&str]` - The lines of the manifest
+///
+/// ## Returns
+/// * `Option<usize>` - The index of the line, `None` if the package has no version
+fn package_version_line(lines: &[&str]) -> Option<usize> {
+    let regex_rule = Regex::new(TOML_VERSION_PATTERN).unwrap();
+    let mut section = "";
+
+    lines.iter().position(|line| {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            section = trimmed;
+        }
+
+        (section == "[package]" || section == "[workspace.package]") && regex_rule.is_match(line)
+    })
+}
+
+///
+/// # `parse_manifest_version`
+/// Reads the version of a manifest file.
+///
+/// ## Arguments
+/// * `content` - `&str` - The manifest content
+/// * `file_name` - `&str` - The manifest file name
+///
+/// ## Returns
+/// * `Option<Version>` - The version, `None` if no valid version was found
+fn parse_manifest_version(content: &str, file_name: &str) -> Option<Version> {
+    let (line, pattern) = if is_toml(file_name) {
+        let lines: Vec<&str> = content.lines().collect();
+
+        (lines[package_version_line(&lines)?], TOML_VERSION_PATTERN)
+    } else {
+        (content, JSON_VERSION_PATTERN)
+    };
+
+    let captures = Regex::new(pattern).unwrap().captures(line)?;
+
+    Version::parse(&captures[2]).ok()
+}
+
+///
+/// # `update_manifest_version`
+/// Replaces the version of a manifest file.
+///
+/// `.toml` files get the `version` key of their `[package]` (or `[workspace.package]`)
+/// section replaced, the other files get their first `"version": "..."` entry replaced.
+///
+/// ## Arguments
+/// * `content` - `&str` - The manifest content
+/// * `file_name` - `&str` - The manifest file name
+/// * `version` - `&Version` - The new version
+///
+/// ## Returns
+/// * `Option<String>` - The new content, `None` if no version was found
+pub fn update_manifest_version(
+    content: &str,
+    file_name: &str,
+    version: &Version,
+) -> Option<String> {
+    if !is_toml(file_name) {
+        let regex_rule = Regex::new(JSON_VERSION_PATTERN).unwrap();
+
+        if !regex_rule.is_match(content) {
+            return None;
+        }
+
+        return Some(
+            regex_rule
+                .replace(content, format!("${{1}}{version}${{3}}"))
+                .to_string(),
+        );
+    }
+
+    let regex_rule = Regex::new(TOML_VERSION_PATTERN).unwrap();
+
+    let lines: Vec<&str> = content.lines().collect();
+    let index = package_version_line(&lines)?;
+
+    let mut new_lines: Vec<String> = lines.iter().map(ToString::to_string).collect();
+    new_lines[index] = regex_rule
+        .replace(lines[index], format!("${{1}}{version}${{3}}"))
+        .to_string();
+
+    let mut new_content = new_lines.join("\n");
+    if content.ends_with('\n') {
+        new_content.push('\n');
+    }
+
+    Some(new_content)
+}
+
+///
+/// # `read_manifest_version`
+/// Reads the version of the first manifest file.
+///
+/// ## Arguments
+/// * `project_root` - `&Path` - The project root
+/// * `manifests` - `&[String]` - The manifest files
+///
+/// ## Returns
+/// * `Option<Version>` - The version, `None` if no manifest has a valid version
+fn read_manifest_version(project_root: &Path, manifests: &[String]) -> Option<Version> {
+    manifests.iter().find_map(|manifest| {
+        let content = std::fs::read_to_string(project_root.join(manifest)).ok()?;
+
+        parse_manifest_version(&content, manifest)
+    })
+}
+
+///
+/// # `refresh_lockfile`
+/// Writes the new version of the workspace packages in the `Cargo.lock` of a bumped `Cargo.toml`.
+/// Only the workspace packages are updated, the dependencies stay locked.
+///
+/// ## Arguments
+/// * `manifest_path` - `&Path` - The bumped manifest
+///
+/// ## Returns
+/// * `Result<Option<PathBuf>, String>` - The refreshed lock file, `None` if there is none
+fn refresh_lockfile(manifest_path: &Path) -> Result<Option<PathBuf>, String> {
+    if manifest_path
+        .file_name()
+        .is_none_or(|name| name != "Cargo.toml")
+    {
+        return Ok(None);
+    }
+
+    // The lock file of a member sits at the workspace root
+    let Some(lockfile) = manifest_path
+        .ancestors()
+        .skip(1)
+        .map(|folder| folder.join("Cargo.lock"))
+        .find(|lockfile| lockfile.exists())
+    else {
+        return Ok(None);
+    };
+
+    let command = Command::new("cargo")
+        .args(["update", "--workspace", "--offline", "--manifest-path"])
+        .arg(manifest_path)
+        .output()
+        .map_err(|e| format!("couldn't run cargo to refresh the lock file: {e}"))?;
+
+    if command.status.success() {
+        Ok(Some(lockfile))
+    } else {
+        Err(format!(
+            "couldn't refresh {}: {}",
+            lockfile.display(),
+            String::from_utf8_lossy(&command.stderr).trim()
+        ))
+    }
+}
+
+///
+/// # `release`
+/// Proposes the next version, bumps the manifests, commits and creates an annotated tag.
+///
+/// ## Arguments
+/// * `project_root` - `&Path` - The project root
+/// * `config` - `&ReleaseConfig` - The release configuration
+/// * `dry_run` - `bool` - Only print what would be done
+/// * `push_args` - `Option<Vec<String>>` - Push after tagging with these args
+/// * `push_config` - `&PushConfig` - The push configuration
+/// * `commit_options` - `&CommitOptions` - The release commit options
+/// * `verbose` - `bool` - Verbose the operation
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the release
+pub fn release(
+    project_root: &Path,
+    config: &ReleaseConfig,
+    dry_run: bool,
+    push_args: Option<Vec<String>>,
+    push_config: &PushConfig,
+    commit_options: &CommitOptions,
+    verbose: bool,
+) -> Result<(), String> {
+    let last_tag = get_last_tag();
+
+    let current_version = last_tag
+        .as_ref()
+        .and_then(|tag| Version::parse(tag.trim_start_matches(&config.tag_prefix)).ok())
+        .or_else(|| read_manifest_version(project_root, &config.manifests))
+        .unwrap_or_else(|| Version::new(0, 0, 0));
+
+    let range = last_tag
+        .as_ref()
+        .map_or_else(|| "HEAD".to_string(), |tag| format!("{tag}..HEAD"));
+    let messages = get_commit_messages(&range);
+
+    let mut bump: Option<Bump> = None;
+    for message in &messages {
+        let commit_bump = classify_commit(message);
+
+        if verbose {
+            let label = commit_bump.map_or("-".to_string(), |bump| format!("{bump:?}"));
+            println!(
+                "  {label:<6} {}",
+                message.lines().next().unwrap_or_default()
+            );
+        }
+
+        bump = bump.max(commit_bump);
+    }
+
+    let Some(bump) = bump else {
+        println!(
+            "{} {} commit(s) since {}.",
+            Yellow.bold().paint("Nothing to release:"),
+            messages.len(),
+            last_tag.as_deref().unwrap_or("the first commit")
+        );
+
+        return Ok(());
+    };
+
+    let version = next_version(&current_version, bump);
+    let tag = format!("{}{version}", config.tag_prefix);
+
+    println!(
+        "{} commit(s) since {}: {current_version} → {} ({bump:?})",
+        messages.len(),
+        last_tag.as_deref().unwrap_or("the first commit"),
+        Green.bold().paint(version.to_string())
+    );
+
+    // Every manifest is checked before anything is written
+    let mut bumped_manifests: Vec<(PathBuf, String)> = Vec::new();
+    for manifest in &config.manifests {
+        let manifest_path = project_root.join(manifest);
+
+        let content = std::fs::read_to_string(&manifest_path)
+            .map_err(|_| format!("manifest {manifest} not found."))?;
+        let new_content = update_manifest_version(&content, manifest, &version)
+            .ok_or_else(|| format!("no version found in {manifest}."))?;
+
+        bumped_manifests.push((manifest_path, new_content));
+    }
+
+    if dry_run {
+        for manifest in &config.manifests {
+            println!("  would update {manifest}");
+        }
+        println!("  would commit and tag {tag}");
+
+        return Ok(());
+    }
+
+    // The release commit only holds the manifests
+    let staged: Vec<String> = get_diff_files(&["--cached"])
+        .into_iter()
+        .filter(|file| !config.manifests.contains(file))
+        .collect();
+    if !staged.is_empty() {
+        return Err(format!(
+            "other changes are staged ({}), commit or unstage them first.",
+            staged.join(", ")
+        ));
+    }
+
+    if !Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Release {tag} ?"))
+        .default(true)
+        .interact()
+        .unwrap()
+    {
+        println!("Bye !");
+        return Ok(());
+    }
+
+    let mut updated_files: Vec<String> = Vec::new();
+    for (manifest_path, new_content) in &bumped_manifests {
+        std::fs::write(manifest_path, new_content).map_err(|e| e.to_string())?;
+        updated_files.push(manifest_path.display().to_string());
+
+        if verbose {
+            println!(
+                "  {} {}",
+                manifest_path.display(),
+                Green.bold().paint("updated")
+            );
+        }
+    }
+
+    for (manifest_path, _) in &bumped_manifests {
+        if let Some(lockfile) = refresh_lockfile(manifest_path)? {
+            let lockfile = lockfile.display().to_string();
+
+            if !updated_files.contains(&lockfile) {
+                updated_files.push(lockfile);
+            }
+        }
+    }
+
+    if !updated_files.is_empty() {
+        add_files(&updated_files)?;
+    }
+
+    let message = config
+        .message
+        .replace("{commit_nb}", &(get_current_commit_nb() + 1).to_string())
+        .replace("{branch}", &get_current_branch())
+        .replace("{version}", &version.to_string())
+        .replace("{tag}", &tag);
+
+    commit(&message, commit_options, verbose)?;
+    create_annotated_tag(&tag, &format!("Release {tag}"), verbose)?;
+
+    if let Some(args) = push_args {
+        let mut args = args;
+        args.push("--follow-tags".to_string());
+
+        smart_push(Some(args), push_config, verbose)?;
+    }
+
+    Ok(())
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+
+    use super::{
+        classify_commit, next_version, parse_manifest_version, update_manifest_version, Bump,
+    };
+
+    #[test]
+    fn test_classify_commit() {
+        assert_eq!(classify_commit("[3] (feat on main) Add"), Some(Bump::Minor));
+        assert_eq!(classify_commit("[3] (fix on main) Fix"), Some(Bump::Patch));
+        assert_eq!(
+            classify_commit("[3] (feat! on main) Break"),
+            Some(Bump::Major)
+        );
+        assert_eq!(
+            classify_commit("fix: Fix\n\nBREAKING CHANGE: the api"),
+            Some(Bump::Major)
+        );
+        assert_eq!(classify_commit("[3] (chore on main) Deps"), None);
+        assert_eq!(classify_commit("Random message"), None);
+    }
+
+    #[test]
+    fn test_next_version() {
+        let current = Version::new(1, 2, 3);
+
+        assert_eq!(next_version(&current, Bump::Major), Version::new(2, 0, 0));
+        assert_eq!(next_version(&current, Bump::Minor), Version::new(1, 3, 0));
+        assert_eq!(next_version(&current, Bump::Patch), Version::new(1, 2, 4));
+    }
+
+    #[test]
+    fn test_update_manifest_version() {
+        let cargo = "[package]\nname = \"a\"\nversion = \"1.2.2\"\n\n[dependencies]\nb = { version = \"1\" }\n";
+
+        assert_eq!(
+            update_manifest_version(cargo, "Cargo.toml", &Version::new(1, 3, 0)).unwrap(),
+            "[package]\nname = \"a\"\nversion = \"1.3.0\"\n\n[dependencies]\nb = { version = \"1\" }\n"
+        );
+
+        let package = "{\n  \"name\": \"a\",\n  \"version\": \"0.1.0\"\n}";
+
+        assert_eq!(
+            update_manifest_version(package, "package.json", &Version::new(0, 2, 0)).unwrap(),
+            "{\n  \"name\": \"a\",\n  \"version\": \"0.2.0\"\n}"
+        );
+
+        assert_eq!(
+            update_manifest_version("[dependencies]\n", "Cargo.toml", &Version::new(1, 0, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_manifest_version() {
+        let workspace = "[workspace.dependencies]\nserde = { version = \"1.0.0\" }\nsemver = \"1.0.0\"\n\n[workspace.package]\nversion = \"0.4.2\"\n";
+
+        assert_eq!(
+            parse_manifest_version(workspace, "Cargo.toml"),
+            Some(Version::new(0, 4, 2))
+        );
+        assert_eq!(
+            parse_manifest_version("{\n  \"version\": \"2.0.1\"\n}", "package.json"),
+            Some(Version::new(2, 0, 1))
+        );
+        assert_eq!(
+            parse_manifest_version("[dependencies]\nversion = \"1.0.0\"\n", "Cargo.toml"),
+            None
+        );
+    }
+}
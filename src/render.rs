@@ -0,0 +1,318 @@
+///
+/// # `render.rs`
+/// Turns the markdown draft into a properly formatted git commit message.
+///
+/// Every step can be turned off in the `[render]` configuration.
+// Imports ================================================================================= Imports
+use regex::Regex;
+
+use crate::config::RenderConfig;
+use crate::draft::{is_group_heading, parse_file_descriptions, parse_header, TRAILER_PATTERN};
+
+// Constants  ===========================================================================  Constants
+/// Width of a tab when wrapping the lines
+const TAB_WIDTH: usize = 4;
+
+// Functions  ===========================================================================  Functions
+///
+/// # `drop_empty_bullets`
+/// Removes the file bullets that have no description.
+/// A group heading without description is kept while a file under it is described.
+///
+/// ## Arguments
+/// * `lines` - `Vec<String>` - The message lines
+///
+/// ## Returns
+/// * `Vec<String>` - The lines without the empty bullets
+fn drop_empty_bullets(lines: Vec<String>) -> Vec<String> {
+    let bullet_rule = Regex::new(r"^- `([^`]+)`:(.*)$").unwrap();
+
+    let described_files: Vec<String> = parse_file_descriptions(&lines.join("\n"))
+        .into_iter()
+        .filter(|described| !is_group_heading(&described.path) && !described.description.is_empty())
+        .map(|described| described.path)
+        .collect();
+
+    let mut kept: Vec<String> = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = &lines[index];
+
+        let Some(captures) = bullet_rule.captures(line) else {
+            kept.push(line.clone());
+            index += 1;
+            continue;
+        };
+
+        // The description is made of the following blank or indented lines
+        let mut end = index + 1;
+        while end < lines.len()
+            && (lines[end].trim().is_empty() || lines[end].starts_with(char::is_whitespace))
+        {
+            end += 1;
+        }
+
+        let path = &captures[1];
+        let is_empty = captures[2].trim().is_empty()
+            && lines[index + 1..end]
+                .iter()
+                .all(|line| line.trim().is_empty())
+            && !(is_group_heading(path)
+                && described_files.iter().any(|file| file.starts_with(path)));
+
+        if !is_empty {
+            kept.extend(lines[index..end].iter().cloned());
+        }
+
+        index = end;
+    }
+
+    kept
+}
+
+///
+/// # `strip_markdown`
+/// Removes the backticks and the bold / italic markers of a line.
+///
+/// ## Arguments
+/// * `line` - `&str` - The line
+///
+/// ## Returns
+/// * `String` - The line without markdown
+fn strip_markdown(line: &str) -> String {
+    let emphasis_rule = Regex::new(r"(\*\*|__)(.+?)(\*\*|__)").unwrap();
+
+    emphasis_rule.replace_all(line, "$2").replace('`', "")
+}
+
+///
+/// # `display_width`
+/// Returns the width of a text, tabs counting as `TAB_WIDTH` columns.
+///
+/// ## Arguments
+/// * `text` - `&str` - The text
+///
+/// ## Returns
+/// * `usize` - The width
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|char| if char == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+///
+/// # `wrap_line`
+/// Wraps a line at the given width, keeping its indentation.
+/// The continuation lines of a `- ` bullet are aligned with its text.
+/// Words longer than the width are never split.
+///
+/// ## Arguments
+/// * `line` - `&str` - The line
+/// * `width` - `usize` - The maximum width
+///
+/// ## Returns
+/// * `Vec<String>` - The wrapped lines
+fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    if display_width(line) <= width {
+        return vec![line.to_string()];
+    }
+
+    let text = line.trim_start();
+    let indent = &line[..line.len() - text.len()];
+
+    let continuation_indent = if text.starts_with("- ") || text.starts_with("* ") {
+        format!("{indent}  ")
+    } else {
+        indent.to_string()
+    };
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = indent.to_string();
+    let mut current_is_empty = true;
+
+    for word in text.split(' ').filter(|word| !word.is_empty()) {
+        if !current_is_empty && display_width(&current) + 1 + display_width(word) > width {
+            lines.push(current);
+            current = continuation_indent.clone();
+            current_is_empty = true;
+        }
+
+        if !current_is_empty {
+            current.push(' ');
+        }
+        current.push_str(word);
+        current_is_empty = false;
+    }
+
+    lines.push(current);
+
+    lines
+}
+
+///
+/// # `collapse_blank_lines`
+/// Collapses the consecutive blank lines and removes the leading and trailing ones.
+///
+/// ## Arguments
+/// * `lines` - `Vec<String>` - The message lines
+///
+/// ## Returns
+/// * `Vec<String>` - The collapsed lines
+fn collapse_blank_lines(lines: Vec<String>) -> Vec<String> {
+    let mut collapsed: Vec<String> = Vec::new();
+
+    for line in lines {
+        if line.is_empty() && collapsed.last().is_none_or(String::is_empty) {
+            continue;
+        }
+
+        collapsed.push(line);
+    }
+
+    while collapsed.last().is_some_and(String::is_empty) {
+        collapsed.pop();
+    }
+
+    collapsed
+}
+
+///
+/// # `render_message`
+/// Renders the draft into the final commit message.
+///
+/// ## Arguments
+/// * `draft` - `&str` - The `commit_message.md` content
+/// * `config` - `&RenderConfig` - The rendering configuration
+///
+/// ## Returns
+/// * `String` - The commit message
+pub fn render_message(draft: &str, config: &RenderConfig) -> String {
+    let trailer_rule = Regex::new(TRAILER_PATTERN).unwrap();
+
+    // Whitespace only lines (the tab-indented placeholders) become empty
+    let mut lines: Vec<String> = draft
+        .lines()
+        .filter(|line| !(config.remove_comments && line.starts_with('#')))
+        .map(|line| line.trim_end().to_string())
+        .collect();
+
+    if config.drop_empty_bullets {
+        lines = drop_empty_bullets(lines);
+    }
+
+    if config.strip_markdown {
+        lines = lines.iter().map(|line| strip_markdown(line)).collect();
+    }
+
+    if config.wrap_width > 0 {
+        // The header and the trailers are never wrapped
+        let header_index = lines.iter().position(|line| !line.is_empty());
+
+        lines = lines
+            .iter()
+            .enumerate()
+            .flat_map(|(index, line)| {
+                if Some(index) == header_index || trailer_rule.is_match(line) {
+                    vec![line.clone()]
+                } else {
+                    wrap_line(line, config.wrap_width)
+                }
+            })
+            .collect();
+    }
+
+    if config.collapse_blank_lines {
+        lines = collapse_blank_lines(lines);
+    }
+
+    let mut message = lines.join("\n").trim().to_string();
+    message.push('\n');
+
+    message
+}
+
+///
+/// # `check_subject`
+/// Checks the subject line of a rendered message.
+///
+/// ## Arguments
+/// * `message` - `&str` - The rendered message
+/// * `width` - `usize` - The maximum subject width, `0` to skip the check
+///
+/// ## Returns
+/// * `Vec<String>` - The problems found, empty if the subject is fine
+pub fn check_subject(message: &str, width: usize) -> Vec<String> {
+    let subject = message.lines().next().unwrap_or_default();
+    let mut problems: Vec<String> = Vec::new();
+
+    if parse_header(subject).is_some_and(|header| header.summary.is_empty()) {
+        problems.push("the subject line has no summary after the header".to_string());
+    }
+
+    if width > 0 && subject.chars().count() > width {
+        problems.push(format!(
+            "the subject line is {} characters long (max {width})",
+            subject.chars().count()
+        ));
+    }
+
+    problems
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::{check_subject, render_message, wrap_line};
+    use crate::config::RenderConfig;
+
+    #[test]
+    fn test_render_message() {
+        let draft = "[3] (feat on login) Add the form\n\n\n# Comment\n- `src/form.rs`:\n\n\tAdd the **login** form.\n\n- `src/style.css`:\n\n\t\n\n- `old.rs`: deleted\n\nRefs: PROJ-12\n";
+
+        assert_eq!(
+            render_message(draft, &RenderConfig::default()),
+            "[3] (feat on login) Add the form\n\n- `src/form.rs`:\n\n\tAdd the **login** form.\n\n- `old.rs`: deleted\n\nRefs: PROJ-12\n"
+        );
+
+        let config = RenderConfig {
+            strip_markdown: true,
+            ..RenderConfig::default()
+        };
+
+        assert_eq!(
+            render_message(draft, &config),
+            "[3] (feat on login) Add the form\n\n- src/form.rs:\n\n\tAdd the login form.\n\n- old.rs: deleted\n\nRefs: PROJ-12\n"
+        );
+    }
+
+    #[test]
+    fn test_render_grouped_message() {
+        let draft = "[3] (feat on login) Add the form\n\n- `src/`:\n\n\tAdd the form.\n\n- `src/form.rs`:\n\n\t\n\n- `tests/`:\n\n\t\n\n- `tests/form.rs`:\n\n\tTest it.\n\n- `docs/`:\n\n\t\n\n- `docs/form.md`:\n\n\t\n";
+
+        assert_eq!(
+            render_message(draft, &RenderConfig::default()),
+            "[3] (feat on login) Add the form\n\n- `src/`:\n\n\tAdd the form.\n\n- `tests/`:\n\n- `tests/form.rs`:\n\n\tTest it.\n"
+        );
+    }
+
+    #[test]
+    fn test_wrap_line() {
+        assert_eq!(
+            wrap_line("\tone two three four five", 18),
+            vec!["\tone two three", "\tfour five"]
+        );
+        assert_eq!(
+            wrap_line("- one two three", 10),
+            vec!["- one two", "  three"]
+        );
+        assert_eq!(wrap_line("short", 10), vec!["short"]);
+    }
+
+    #[test]
+    fn test_check_subject() {
+        assert_eq!(check_subject("[3] (feat on login) Add\n", 72).len(), 0);
+        assert_eq!(check_subject("[3] (feat on login)\n", 72).len(), 1);
+        assert_eq!(check_subject("[3] (feat on login) Add\n", 10).len(), 1);
+    }
+}
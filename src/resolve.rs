@@ -0,0 +1,230 @@
+///
+/// # `resolve.rs`
+/// Contains the conflict resolution helper, used after a merge, a rebase
+/// or a stash pop that left conflicts.
+// Imports ================================================================================= Imports
+use std::path::Path;
+
+use ansi_term::Colour::{Green, Yellow};
+use dialoguer::Select;
+
+use crate::git_related::{
+    add_files, checkout_conflict_side, get_config_value, get_conflicted_files, parse_status_line,
+    read_git_status, remove_file, run_merge_tool,
+};
+use crate::my_theme::ColorfulTheme;
+use crate::utils::open_in_editor;
+
+// Functions  ===========================================================================  Functions
+///
+/// # `deleted_by`
+/// Tells which side deleted a file in a delete/modify conflict.
+/// Such a file has a single side, `--ours` or `--theirs` can't check it out.
+///
+/// ## Arguments
+/// * `code` - `&str` - The status code of the conflicted file
+///
+/// ## Returns
+/// * `Option<&str>` - `us` or `them`, `None` if both sides kept the file
+pub fn deleted_by(code: &str) -> Option<&'static str> {
+    match code {
+        "DU" => Some("us"),
+        "UD" => Some("them"),
+        _ => None,
+    }
+}
+
+///
+/// # `count_conflict_hunks`
+/// Counts the conflict hunks of a file content.
+///
+/// ## Arguments
+/// * `content` - `&str` - The file content
+///
+/// ## Returns
+/// * `usize` - The number of `<<<<<<<` markers
+pub fn count_conflict_hunks(content: &str) -> usize {
+    content
+        .lines()
+        .filter(|line| line.starts_with("<<<<<<<"))
+        .count()
+}
+
+///
+/// # `has_conflict_markers`
+/// Checks if a file content still has conflict markers.
+///
+/// ## Arguments
+/// * `content` - `&str` - The file content
+///
+/// ## Returns
+/// * `bool` - `true` if a `<<<<<<<`, `=======` or `>>>>>>>` marker remains
+pub fn has_conflict_markers(content: &str) -> bool {
+    content
+        .lines()
+        .any(|line| line.starts_with("<<<<<<<") || line.starts_with(">>>>>>>") || line == "=======")
+}
+
+///
+/// # `stage_if_resolved`
+/// Stages a file once no conflict marker remains in it.
+///
+/// ## Arguments
+/// * `project_root` - `&Path` - The project root
+/// * `file` - `&str` - The file, relative to the project root
+///
+/// ## Returns
+/// * `bool` - `true` if the file was staged
+fn stage_if_resolved(project_root: &Path, file: &str) -> bool {
+    // A file deleted by the resolution has no content left
+    let content = std::fs::read_to_string(project_root.join(file)).unwrap_or_default();
+
+    if has_conflict_markers(&content) {
+        println!(
+            "{} `{file}` still has {} conflict(s), not staged.",
+            Yellow.bold().paint("Warning:"),
+            count_conflict_hunks(&content)
+        );
+
+        return false;
+    }
+
+    match add_files(&[file.to_string()]) {
+        Ok(()) => {
+            println!("`{file}` {}", Green.bold().paint("resolved and staged."));
+            true
+        }
+        Err(e) => {
+            eprintln!("Couldn't stage `{file}`: {e}");
+            false
+        }
+    }
+}
+
+///
+/// # `resolve_conflicts`
+/// Lets the user resolve the conflicted files one by one: take ours, take theirs,
+/// open in the editor or launch the configured `merge.tool`.
+///
+/// ## Arguments
+/// * `project_root` - `&Path` - The project root
+///
+/// ## Returns
+/// * `usize` - The number of conflicted files left
+pub fn resolve_conflicts(project_root: &Path) -> usize {
+    let merge_tool = get_config_value("merge.tool");
+
+    loop {
+        let status = read_git_status();
+        let conflicted_files = get_conflicted_files(&status);
+
+        if conflicted_files.is_empty() {
+            println!("{}", Green.bold().paint("No conflict left."));
+            return 0;
+        }
+
+        let mut items: Vec<String> = conflicted_files
+            .iter()
+            .map(|file| {
+                let content = std::fs::read_to_string(project_root.join(file)).unwrap_or_default();
+
+                format!("{file} ({} conflict(s))", count_conflict_hunks(&content))
+            })
+            .collect();
+        items.push("Quit".to_string());
+
+        let index = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Conflicted file")
+            .default(0)
+            .items(&items)
+            .interact()
+            .unwrap();
+
+        let Some(file) = conflicted_files.get(index) else {
+            return conflicted_files.len();
+        };
+
+        let deleted_by = status
+            .lines()
+            .filter_map(parse_status_line)
+            .find(|(_, path)| path == file)
+            .and_then(|(code, _)| deleted_by(&code));
+
+        // The surviving side is already in the working tree
+        if let Some(side) = deleted_by {
+            let action = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!(
+                    "`{file}` was deleted by {side} and modified on the other side"
+                ))
+                .default(0)
+                .items(&["Delete it", "Keep the modified version", "Back"])
+                .interact()
+                .unwrap();
+
+            let result = match action {
+                0 => remove_file(file),
+                1 => add_files(std::slice::from_ref(file)),
+                _ => continue,
+            };
+
+            match result {
+                Ok(()) => println!("`{file}` {}", Green.bold().paint("resolved and staged.")),
+                Err(e) => eprintln!("Couldn't resolve `{file}`: {e}"),
+            }
+            continue;
+        }
+
+        let mut actions = vec!["Take ours", "Take theirs", "Open in the editor"];
+        if let Some(tool) = &merge_tool {
+            actions.push(tool);
+        }
+        actions.push("Back");
+
+        let action = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Resolve `{file}`"))
+            .default(0)
+            .items(&actions)
+            .interact()
+            .unwrap();
+
+        let result = match action {
+            0 => checkout_conflict_side(file, "--ours"),
+            1 => checkout_conflict_side(file, "--theirs"),
+            2 => {
+                open_in_editor(&project_root.join(file));
+                Ok(())
+            }
+            3 if merge_tool.is_some() => run_merge_tool(file),
+            _ => continue,
+        };
+
+        match result {
+            Ok(()) => {
+                stage_if_resolved(project_root, file);
+            }
+            Err(e) => eprintln!("Couldn't resolve `{file}`: {e}"),
+        }
+    }
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::{count_conflict_hunks, deleted_by, has_conflict_markers};
+
+    #[test]
+    fn test_conflict_markers() {
+        let content = "fn main() {\n<<<<<<< HEAD\n    ours();\n=======\n    theirs();\n>>>>>>> login\n}\n<<<<<<< HEAD\na\n=======\nb\n>>>>>>> login\n";
+
+        assert_eq!(count_conflict_hunks(content), 2);
+        assert!(has_conflict_markers(content));
+        assert!(!has_conflict_markers("fn main() {\n    ours();\n}\n"));
+    }
+
+    #[test]
+    fn test_deleted_by() {
+        assert_eq!(deleted_by("DU"), Some("us"));
+        assert_eq!(deleted_by("UD"), Some("them"));
+        assert_eq!(deleted_by("UU"), None);
+    }
+}
@@ -0,0 +1,348 @@
+///
+/// # `runner.rs`
+/// Runs a subprocess while streaming its output live.
+///
+/// The standard input is inherited so hooks can prompt, the standard output and
+/// error are forwarded as they arrive and kept in a transcript for the error reports.
+/// When nothing has to be captured, they are left to the terminal so the subprocess
+/// still sees a TTY (progress, colours).
+// Imports ================================================================================= Imports
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use ansi_term::Colour::{Fixed, Yellow};
+
+// Constants  ===========================================================================  Constants
+/// Delay between two checks of the subprocess status
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Delay given to the forwarding threads after a timeout, the output left is dropped
+const DRAIN_DELAY: Duration = Duration::from_millis(500);
+/// Prefix of the forwarded lines in verbose mode
+const LINE_PREFIX: &str = "│ ";
+
+// Structs  ===============================================================================  Structs
+/// How a subprocess is run.
+#[derive(Debug, Default, Clone)]
+pub struct RunOptions {
+    /// Prefix and colour the forwarded lines
+    pub verbose: bool,
+    /// Kill the subprocess after this delay
+    pub timeout: Option<Duration>,
+    /// Leave the standard output and error to the terminal, nothing is captured
+    pub passthrough: bool,
+    /// Environment variables added to the subprocess
+    pub env: Vec<(String, String)>,
+}
+
+/// The result of a subprocess.
+#[derive(Debug, Default)]
+pub struct RunOutput {
+    /// The subprocess exited successfully
+    pub success: bool,
+    /// The subprocess was killed by the timeout
+    pub timed_out: bool,
+    /// The standard error, partial if the subprocess timed out
+    pub stderr: String,
+    /// The standard output and error, interleaved as they arrived, partial if the subprocess timed out
+    pub transcript: String,
+}
+
+/// The stream a chunk comes from.
+#[derive(Debug, Clone, Copy)]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `prefix_lines`
+/// Prefixes the lines of a chunk, a line may start in a previous chunk.
+///
+/// ## Arguments
+/// * `chunk` - `&str` - The chunk
+/// * `prefix` - `&str` - The prefix
+/// * `at_line_start` - `&mut bool` - If the previous chunk ended a line, updated for the next one
+///
+/// ## Returns
+/// * `String` - The prefixed chunk
+fn prefix_lines(chunk: &str, prefix: &str, at_line_start: &mut bool) -> String {
+    let mut prefixed = String::new();
+
+    for line in chunk.split_inclusive('\n') {
+        if *at_line_start {
+            prefixed.push_str(prefix);
+        }
+
+        prefixed.push_str(line);
+        *at_line_start = line.ends_with('\n');
+    }
+
+    prefixed
+}
+
+///
+/// # `decode_chunk`
+/// Decodes a chunk of a stream, a character may be split across two chunks.
+///
+/// ## Arguments
+/// * `pending` - `&mut Vec<u8>` - The bytes of an incomplete character, updated for the next chunk
+/// * `bytes` - `&[u8]` - The chunk
+///
+/// ## Returns
+/// * `String` - The decoded text, the invalid bytes are replaced
+fn decode_chunk(pending: &mut Vec<u8>, bytes: &[u8]) -> String {
+    pending.extend_from_slice(bytes);
+
+    // Only an incomplete character at the very end is kept for later
+    let complete = match std::str::from_utf8(pending) {
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => pending.len(),
+    };
+
+    let rest = pending.split_off(complete);
+    let decoded = String::from_utf8_lossy(pending).to_string();
+    *pending = rest;
+
+    decoded
+}
+
+///
+/// # `forward`
+/// Forwards a stream of the subprocess as it arrives.
+///
+/// ## Arguments
+/// * `reader` - `impl Read` - The stream
+/// * `stream` - `Stream` - Which stream it is
+/// * `verbose` - `bool` - Prefix and colour the lines
+/// * `captured` - `Option<Arc<Mutex<String>>>` - The buffer of this stream, if it is kept apart
+/// * `transcript` - `Arc<Mutex<String>>` - The buffer shared by both streams
+///
+/// ## Returns
+/// * `JoinHandle<()>` - The forwarding thread
+fn forward(
+    mut reader: impl Read + Send + 'static,
+    stream: Stream,
+    verbose: bool,
+    captured: Option<Arc<Mutex<String>>>,
+    transcript: Arc<Mutex<String>>,
+) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        let prefix = match stream {
+            Stream::Stdout => Fixed(8).paint(LINE_PREFIX).to_string(),
+            Stream::Stderr => Yellow.paint(LINE_PREFIX).to_string(),
+        };
+
+        let mut buffer = [0u8; 4096];
+        let mut pending: Vec<u8> = Vec::new();
+        let mut at_line_start = true;
+
+        loop {
+            let read = reader.read(&mut buffer).unwrap_or(0);
+
+            // A truncated character left at the end is flushed as is
+            let chunk = if read == 0 {
+                String::from_utf8_lossy(&std::mem::take(&mut pending)).to_string()
+            } else {
+                decode_chunk(&mut pending, &buffer[..read])
+            };
+
+            let shown = if verbose {
+                prefix_lines(&chunk, &prefix, &mut at_line_start)
+            } else {
+                chunk.clone()
+            };
+
+            // The output is best effort, a closed terminal must not stop the capture
+            let _ = match stream {
+                Stream::Stdout => {
+                    let mut stdout = std::io::stdout();
+                    stdout
+                        .write_all(shown.as_bytes())
+                        .and_then(|()| stdout.flush())
+                }
+                Stream::Stderr => std::io::stderr().write_all(shown.as_bytes()),
+            };
+
+            if let Some(captured) = &captured {
+                captured.lock().unwrap().push_str(&chunk);
+            }
+            transcript.lock().unwrap().push_str(&chunk);
+
+            if read == 0 {
+                break;
+            }
+        }
+    })
+}
+
+///
+/// # `run_streaming`
+/// Runs a subprocess, streaming its output live.
+///
+/// ## Arguments
+/// * `program` - `&str` - The program
+/// * `args` - `&[String]` - The arguments
+/// * `options` - `&RunOptions` - How to run it
+///
+/// ## Returns
+/// * `RunOutput` - The result of the subprocess
+pub fn run_streaming(program: &str, args: &[String], options: &RunOptions) -> RunOutput {
+    let output_stdio = || {
+        if options.passthrough {
+            Stdio::inherit()
+        } else {
+            Stdio::piped()
+        }
+    };
+
+    let mut child = match Command::new(program)
+        .args(args)
+        .envs(options.env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::inherit())
+        .stdout(output_stdio())
+        .stderr(output_stdio())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            return RunOutput {
+                stderr: e.to_string(),
+                transcript: e.to_string(),
+                ..RunOutput::default()
+            }
+        }
+    };
+
+    let stderr = Arc::new(Mutex::new(String::new()));
+    let transcript = Arc::new(Mutex::new(String::new()));
+
+    let threads = [
+        child.stdout.take().map(|reader| {
+            forward(
+                reader,
+                Stream::Stdout,
+                options.verbose,
+                None,
+                Arc::clone(&transcript),
+            )
+        }),
+        child.stderr.take().map(|reader| {
+            forward(
+                reader,
+                Stream::Stderr,
+                options.verbose,
+                Some(Arc::clone(&stderr)),
+                Arc::clone(&transcript),
+            )
+        }),
+    ];
+
+    let started = Instant::now();
+    let (success, timed_out) = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break (status.success(), false),
+            Ok(None) => {
+                if options
+                    .timeout
+                    .is_some_and(|timeout| started.elapsed() >= timeout)
+                {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    break (false, true);
+                }
+
+                std::thread::sleep(POLL_INTERVAL);
+            }
+            Err(_) => break (false, false),
+        }
+    };
+
+    // A killed subprocess may leave children holding the pipes open, their
+    // threads are left behind after a short delay and end with the children
+    let drain_deadline = Instant::now() + DRAIN_DELAY;
+    for thread in threads.into_iter().flatten() {
+        while timed_out && !thread.is_finished() && Instant::now() < drain_deadline {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+
+        if !timed_out || thread.is_finished() {
+            let _ = thread.join();
+        }
+    }
+
+    let stderr = stderr.lock().unwrap().clone();
+    let transcript = transcript.lock().unwrap().clone();
+
+    RunOutput {
+        success,
+        timed_out,
+        stderr,
+        transcript,
+    }
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{decode_chunk, prefix_lines, run_streaming, RunOptions};
+
+    #[test]
+    fn test_prefix_lines() {
+        let mut at_line_start = true;
+
+        assert_eq!(
+            prefix_lines("one\ntw", "> ", &mut at_line_start),
+            "> one\n> tw"
+        );
+        assert_eq!(prefix_lines("o\n", "> ", &mut at_line_start), "o\n");
+        assert!(at_line_start);
+    }
+
+    #[test]
+    fn test_decode_chunk() {
+        let mut pending: Vec<u8> = Vec::new();
+        let bytes = "é!".as_bytes();
+
+        assert_eq!(decode_chunk(&mut pending, &bytes[..1]), "");
+        assert_eq!(pending, vec![bytes[0]]);
+        assert_eq!(decode_chunk(&mut pending, &bytes[1..]), "é!");
+        assert!(pending.is_empty());
+
+        // An invalid byte in the middle is not waited for
+        assert_eq!(decode_chunk(&mut pending, b"a\xffb"), "a\u{fffd}b");
+    }
+
+    #[test]
+    fn test_run_streaming() {
+        let args = vec![
+            "-c".to_string(),
+            "echo out; echo $NAME >&2; exit 3".to_string(),
+        ];
+        let options = RunOptions {
+            env: vec![("NAME".to_string(), "err".to_string())],
+            ..RunOptions::default()
+        };
+        let output = run_streaming("sh", &args, &options);
+
+        assert!(!output.success);
+        assert_eq!(output.stderr, "err\n");
+        assert!(output.transcript.contains("out\n"));
+
+        // The `sleep` child keeps the pipes open after `sh` is killed
+        let args = vec!["-c".to_string(), "sleep 5; echo done".to_string()];
+        let options = RunOptions {
+            timeout: Some(Duration::from_millis(100)),
+            ..RunOptions::default()
+        };
+        let started = Instant::now();
+
+        assert!(run_streaming("sh", &args, &options).timed_out);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
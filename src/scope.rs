@@ -0,0 +1,294 @@
+///
+/// # `scope.rs`
+/// Infers the commit scope from the changed files.
+///
+/// The configured `[scopes]` globs are checked first, then the packages of the
+/// Cargo workspace (`workspace.members`) and of the npm workspace (`workspaces`).
+// Imports ================================================================================= Imports
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use globset::Glob;
+
+// Structs ================================================================================= Structs
+/// A workspace package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    /// The package name
+    pub name: String,
+    /// The package folder, relative to the project root
+    pub path: String,
+}
+
+/// The scopes found for the changed files.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct InferredScopes {
+    /// The scopes, without duplicates, in the files order
+    pub scopes: Vec<String>,
+    /// The files matching no mapping and no package (`README.md`, `.github/...`)
+    pub unscoped: Vec<String>,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `expand_members`
+/// Expands the workspace members globs into folders.
+///
+/// ## Arguments
+/// * `project_root` - `&Path` - The project root
+/// * `members` - `&[String]` - The members (ex: `crates/*`)
+///
+/// ## Returns
+/// * `Vec<String>` - The member folders, relative to the project root
+fn expand_members(project_root: &Path, members: &[String]) -> Vec<String> {
+    let mut folders: Vec<String> = Vec::new();
+
+    for member in members {
+        let pattern = project_root.join(member).display().to_string();
+
+        let Ok(paths) = glob::glob(&pattern) else {
+            continue;
+        };
+
+        for path in paths.flatten().filter(|path| path.is_dir()) {
+            if let Ok(relative) = path.strip_prefix(project_root) {
+                folders.push(relative.display().to_string());
+            }
+        }
+    }
+
+    folders
+}
+
+///
+/// # `read_cargo_packages`
+/// Reads the packages of the Cargo workspace.
+///
+/// ## Arguments
+/// * `project_root` - `&Path` - The project root
+///
+/// ## Returns
+/// * `Vec<Package>` - The packages, empty if the project isn't a Cargo workspace
+pub fn read_cargo_packages(project_root: &Path) -> Vec<Package> {
+    let Some(manifest) = std::fs::read_to_string(project_root.join("Cargo.toml"))
+        .ok()
+        .and_then(|content| content.parse::<toml::Table>().ok())
+    else {
+        return Vec::new();
+    };
+
+    let members: Vec<String> = manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(|members| members.as_array())
+        .map(|members| {
+            members
+                .iter()
+                .filter_map(|member| member.as_str().map(ToString::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    expand_members(project_root, &members)
+        .into_iter()
+        .map(|path| {
+            let name = std::fs::read_to_string(project_root.join(&path).join("Cargo.toml"))
+                .ok()
+                .and_then(|content| content.parse::<toml::Table>().ok())
+                .and_then(|manifest| {
+                    manifest
+                        .get("package")?
+                        .get("name")?
+                        .as_str()
+                        .map(ToString::to_string)
+                });
+
+            Package {
+                name: name.unwrap_or_else(|| folder_name(&path)),
+                path,
+            }
+        })
+        .collect()
+}
+
+///
+/// # `read_npm_packages`
+/// Reads the packages of the npm (or yarn, pnpm) workspace.
+///
+/// ## Arguments
+/// * `project_root` - `&Path` - The project root
+///
+/// ## Returns
+/// * `Vec<Package>` - The packages, empty if the project isn't an npm workspace
+fn read_npm_packages(project_root: &Path) -> Vec<Package> {
+    let Some(manifest) = std::fs::read_to_string(project_root.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    else {
+        return Vec::new();
+    };
+
+    // `"workspaces": [...]` or `"workspaces": { "packages": [...] }`
+    let workspaces = manifest.get("workspaces").and_then(|workspaces| {
+        workspaces
+            .as_array()
+            .or_else(|| workspaces.get("packages")?.as_array())
+    });
+
+    let members: Vec<String> = workspaces
+        .map(|members| {
+            members
+                .iter()
+                .filter_map(|member| member.as_str().map(ToString::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    expand_members(project_root, &members)
+        .into_iter()
+        .map(|path| {
+            let name = std::fs::read_to_string(project_root.join(&path).join("package.json"))
+                .ok()
+                .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+                .and_then(|manifest| manifest.get("name")?.as_str().map(ToString::to_string))
+                // `@team/package` → `package`
+                .map(|name| name.rsplit('/').next().unwrap_or(&name).to_string());
+
+            Package {
+                name: name.unwrap_or_else(|| folder_name(&path)),
+                path,
+            }
+        })
+        .collect()
+}
+
+///
+/// # `folder_name`
+/// Returns the last component of a path.
+///
+/// ## Arguments
+/// * `path` - `&str` - The path
+///
+/// ## Returns
+/// * `String` - The last component
+fn folder_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map_or(path.to_string(), |name| name.to_string_lossy().to_string())
+}
+
+///
+/// # `read_workspace_packages`
+/// Reads the packages of the Cargo and npm workspaces.
+///
+/// ## Arguments
+/// * `project_root` - `&Path` - The project root
+///
+/// ## Returns
+/// * `Vec<Package>` - The packages
+pub fn read_workspace_packages(project_root: &Path) -> Vec<Package> {
+    let mut packages = read_cargo_packages(project_root);
+    packages.extend(read_npm_packages(project_root));
+
+    packages
+}
+
+///
+/// # `infer_scopes`
+/// Finds the scopes of the changed files.
+///
+/// ## Arguments
+/// * `files` - `&[String]` - The changed files, relative to the project root
+/// * `packages` - `&[Package]` - The workspace packages
+/// * `mappings` - `&BTreeMap<String, String>` - The configured path glob → scope map
+///
+/// ## Returns
+/// * `InferredScopes` - The scopes and the files outside of any scope
+pub fn infer_scopes(
+    files: &[String],
+    packages: &[Package],
+    mappings: &BTreeMap<String, String>,
+) -> InferredScopes {
+    let mappings: Vec<(globset::GlobMatcher, &String)> = mappings
+        .iter()
+        .filter_map(|(glob, scope)| Some((Glob::new(glob).ok()?.compile_matcher(), scope)))
+        .collect();
+
+    let mut inferred = InferredScopes::default();
+
+    for file in files {
+        let mapped_scope = mappings
+            .iter()
+            .find(|(matcher, _)| matcher.is_match(file))
+            .map(|(_, scope)| (*scope).clone());
+
+        // The deepest package containing the file
+        let package_scope = || {
+            packages
+                .iter()
+                .filter(|package| Path::new(file).starts_with(&package.path))
+                .max_by_key(|package| package.path.len())
+                .map(|package| package.name.clone())
+        };
+
+        match mapped_scope.or_else(package_scope) {
+            Some(scope) if !inferred.scopes.contains(&scope) => inferred.scopes.push(scope),
+            Some(_) => {}
+            None => inferred.unscoped.push(file.clone()),
+        }
+    }
+
+    inferred
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{infer_scopes, InferredScopes, Package};
+
+    #[test]
+    fn test_infer_scopes() {
+        let packages = vec![
+            Package {
+                name: "core".to_string(),
+                path: "crates/core".to_string(),
+            },
+            Package {
+                name: "cli".to_string(),
+                path: "crates/cli".to_string(),
+            },
+        ];
+        let mappings = BTreeMap::from([("docs/**".to_string(), "docs".to_string())]);
+
+        let files =
+            |files: &[&str]| -> Vec<String> { files.iter().map(ToString::to_string).collect() };
+
+        assert_eq!(
+            infer_scopes(
+                &files(&["crates/core/src/lib.rs", "crates/core/Cargo.toml"]),
+                &packages,
+                &mappings
+            ),
+            InferredScopes {
+                scopes: files(&["core"]),
+                unscoped: Vec::new()
+            }
+        );
+        assert_eq!(
+            infer_scopes(
+                &files(&["crates/cli/src/main.rs", "docs/guide.md", "README.md"]),
+                &packages,
+                &mappings
+            ),
+            InferredScopes {
+                scopes: files(&["cli", "docs"]),
+                unscoped: files(&["README.md"])
+            }
+        );
+        assert!(infer_scopes(&files(&["README.md"]), &packages, &mappings)
+            .scopes
+            .is_empty());
+    }
+}
@@ -0,0 +1,365 @@
+///
+/// # `split.rs`
+/// Contains the functions used to split one session's changes into several commits.
+///
+/// A split draft holds several commit sections separated by `# --- commit ---` lines,
+/// each with its own header and file bullets. The separator is a comment, a `---`
+/// markdown rule in a body doesn't split it.
+// Imports ================================================================================= Imports
+use std::collections::BTreeMap;
+
+use ansi_term::Colour::{Green, Red};
+
+use crate::config::Config;
+use crate::draft::{append_trailers, is_group_heading, parse_file_descriptions};
+use crate::git_related::{
+    add_files, commit, find_unchanged_paths, get_head_sha, read_git_status, restore_state,
+    unstage_all, write_index_tree, CommitOptions,
+};
+use crate::guards::{report_findings, scan_staged};
+use crate::render::render_message;
+use crate::scope::{infer_scopes, Package};
+
+// Constants  ===========================================================================  Constants
+/// Line separating the commit sections of a draft
+pub const SECTION_SEPARATOR: &str = "# --- commit ---";
+
+// Structs  ===============================================================================  Structs
+/// Changed files proposed as one commit.
+#[derive(Debug, PartialEq)]
+pub struct FileGroup {
+    /// The scope of the files, `None` when grouped by directory
+    pub scope: Option<String>,
+    /// The files
+    pub files: Vec<String>,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `split_sections`
+/// Splits a draft into its commit sections.
+/// The sections holding only blank or `#` comment lines are dropped.
+///
+/// ## Arguments
+/// * `draft` - `&str` - The `commit_message.md` content
+///
+/// ## Returns
+/// * `Vec<String>` - The sections, in the draft order
+pub fn split_sections(draft: &str) -> Vec<String> {
+    let mut sections: Vec<String> = vec![String::new()];
+
+    for line in draft.lines() {
+        if line.trim_end() == SECTION_SEPARATOR {
+            sections.push(String::new());
+        } else if let Some(section) = sections.last_mut() {
+            section.push_str(line);
+            section.push('\n');
+        }
+    }
+
+    sections
+        .into_iter()
+        .filter(|section| {
+            section
+                .lines()
+                .any(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        })
+        .collect()
+}
+
+///
+/// # `group_files`
+/// Groups the changed files by scope, or by top directory for the files without scope.
+///
+/// ## Arguments
+/// * `files` - `&[String]` - The changed files, relative to the project root
+/// * `packages` - `&[Package]` - The workspace packages
+/// * `mappings` - `&BTreeMap<String, String>` - The configured path glob → scope map
+///
+/// ## Returns
+/// * `Vec<FileGroup>` - The groups, in the order of their first file
+pub fn group_files(
+    files: &[String],
+    packages: &[Package],
+    mappings: &BTreeMap<String, String>,
+) -> Vec<FileGroup> {
+    let mut keys: Vec<String> = Vec::new();
+    let mut groups: Vec<FileGroup> = Vec::new();
+
+    for file in files {
+        let scope = infer_scopes(std::slice::from_ref(file), packages, mappings)
+            .scopes
+            .into_iter()
+            .next();
+
+        // The files at the root share the `.` group
+        let key = match &scope {
+            Some(scope) => format!("scope:{scope}"),
+            None => match file.split_once('/') {
+                Some((directory, _)) => format!("dir:{directory}"),
+                None => "dir:.".to_string(),
+            },
+        };
+
+        match keys.iter().position(|known| *known == key) {
+            Some(index) => groups[index].files.push(file.clone()),
+            None => {
+                keys.push(key);
+                groups.push(FileGroup {
+                    scope,
+                    files: vec![file.clone()],
+                });
+            }
+        }
+    }
+
+    groups
+}
+
+///
+/// # `described_files`
+/// Returns the files having a bullet in a draft.
+/// A renamed file bullet (`` - `old -> new`: ``) gives both paths.
+///
+/// ## Arguments
+/// * `draft` - `&str` - The draft or the draft section
+///
+/// ## Returns
+/// * `Vec<String>` - The described files
+pub fn described_files(draft: &str) -> Vec<String> {
+    parse_file_descriptions(draft)
+        .into_iter()
+        .filter(|description| !is_group_heading(&description.path))
+        .flat_map(|description| {
+            description
+                .path
+                .split(" -> ")
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+        })
+        .collect()
+}
+
+///
+/// # `check_sections`
+/// Checks that every section describes changed files, and that no file
+/// is described twice.
+///
+/// ## Arguments
+/// * `sections` - `&[String]` - The draft sections
+/// * `status` - `&str` - The `git status --porcelain` output
+///
+/// ## Returns
+/// * `Result<(), String>` - The first problem found
+pub fn check_sections(sections: &[String], status: &str) -> Result<(), String> {
+    let mut seen: Vec<String> = Vec::new();
+
+    for (index, section) in sections.iter().enumerate() {
+        let files = described_files(section);
+
+        if files.is_empty() {
+            return Err(format!("Section {} doesn't describe any file.", index + 1));
+        }
+
+        let unchanged = find_unchanged_paths(&files, status);
+        if !unchanged.is_empty() {
+            return Err(format!(
+                "These described files have no changes: {}",
+                unchanged.join(", ")
+            ));
+        }
+
+        for file in files {
+            if seen.contains(&file) {
+                return Err(format!("`{file}` is described in several sections."));
+            }
+            seen.push(file);
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// # `stage_draft_files`
+/// Stages exactly the files described in the draft, deleted ones included.
+/// Nothing is staged if a described file has no changes.
+///
+/// ## Arguments
+/// * `draft` - `&str` - The `commit_message.md` content
+/// * `verbose` - `bool` - Verbose the operation
+///
+/// ## Returns
+/// * `Result<(), String>` - The result of the staging
+pub fn stage_draft_files(draft: &str, verbose: bool) -> Result<(), String> {
+    check_sections(&[draft.to_string()], &read_git_status())?;
+
+    let files = described_files(draft);
+
+    unstage_all()?;
+    add_files(&files)?;
+
+    if verbose {
+        println!("Staged {} files from the draft.", files.len());
+    }
+
+    Ok(())
+}
+
+///
+/// # `commit_sections`
+/// Creates one commit per section, staging only the section's files.
+/// If a commit fails, `HEAD` and the index are restored as they were before
+/// the first commit.
+///
+/// ## Arguments
+/// * `sections` - `&[String]` - The draft sections
+/// * `trailers` - `&[String]` - The trailers added to every commit
+/// * `config` - `&Config` - The configuration
+/// * `options` - `&CommitOptions` - The `git commit` options
+/// * `check_guards` - `bool` - Run the `[guards]` on each staged section
+/// * `verbose` - `bool` - Verbose the operation
+///
+/// ## Returns
+/// * `Result<usize, String>` - The number of commits created
+pub fn commit_sections(
+    sections: &[String],
+    trailers: &[String],
+    config: &Config,
+    options: &CommitOptions,
+    check_guards: bool,
+    verbose: bool,
+) -> Result<usize, String> {
+    check_sections(sections, &read_git_status())?;
+
+    let head = get_head_sha();
+    let tree = write_index_tree()?;
+
+    for (index, section) in sections.iter().enumerate() {
+        let message = append_trailers(&render_message(section, &config.render), trailers);
+
+        let result = stage_draft_files(section, verbose)
+            .and_then(|()| {
+                if check_guards && !report_findings(&scan_staged(&config.guards), &config.guards) {
+                    return Err("The guards blocked the commit.".to_string());
+                }
+
+                Ok(())
+            })
+            .and_then(|()| commit(&message, options, verbose).map(|_| ()));
+
+        if let Err(e) = result {
+            eprintln!(
+                "{} section {} failed, rolling back...",
+                Red.bold().paint("Split commit:"),
+                index + 1
+            );
+
+            restore_state(head.as_deref(), &tree)
+                .map_err(|restore_error| format!("{e} (rollback failed: {restore_error})"))?;
+
+            return Err(e);
+        }
+    }
+
+    println!(
+        "{}",
+        Green
+            .bold()
+            .paint(format!("Created {} commits.", sections.len()))
+    );
+
+    Ok(sections.len())
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{check_sections, described_files, group_files, split_sections, FileGroup};
+    use crate::scope::Package;
+
+    #[test]
+    fn test_split_sections() {
+        let draft = "[1] (feat on login) Add the form\n\n- `a.rs`:\n\n\tForm\n\n---\n\n\tMore\n# --- commit ---\n# Comment\n# --- commit ---\n[2] (fix on login) Fix the style\n\n- `b.css`: deleted\n";
+
+        assert_eq!(
+            split_sections(draft),
+            vec![
+                "[1] (feat on login) Add the form\n\n- `a.rs`:\n\n\tForm\n\n---\n\n\tMore\n",
+                "[2] (fix on login) Fix the style\n\n- `b.css`: deleted\n",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_files() {
+        let packages = vec![Package {
+            name: "core".to_string(),
+            path: "crates/core".to_string(),
+        }];
+        let files: Vec<String> = [
+            "crates/core/a.rs",
+            "docs/guide.md",
+            "README.md",
+            "crates/core/b.rs",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+        assert_eq!(
+            group_files(&files, &packages, &BTreeMap::new()),
+            vec![
+                FileGroup {
+                    scope: Some("core".to_string()),
+                    files: vec![
+                        "crates/core/a.rs".to_string(),
+                        "crates/core/b.rs".to_string()
+                    ],
+                },
+                FileGroup {
+                    scope: None,
+                    files: vec!["docs/guide.md".to_string()],
+                },
+                FileGroup {
+                    scope: None,
+                    files: vec!["README.md".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_sections() {
+        let status = " M a.rs\n M b.rs\n";
+        let sections = |sections: &[&str]| -> Vec<String> {
+            sections.iter().map(ToString::to_string).collect()
+        };
+
+        assert!(check_sections(&sections(&["- `a.rs`: x\n", "- `b.rs`: y\n"]), status).is_ok());
+        assert!(check_sections(&sections(&["- `a.rs`: x\n", "- `a.rs`: y\n"]), status).is_err());
+        assert!(check_sections(&sections(&["- `a.rs`: x\n", "- `c.rs`: y\n"]), status).is_err());
+        assert!(check_sections(&sections(&["- `a.rs`: x\n", "No file\n"]), status).is_err());
+
+        let status = "R  old.rs -> new.rs\n M b.rs\n";
+        assert!(check_sections(
+            &sections(&["- `old.rs -> new.rs`: x\n", "- `b.rs`: y\n"]),
+            status
+        )
+        .is_ok());
+        assert!(check_sections(
+            &sections(&["- `old.rs -> new.rs`: x\n", "- `old.rs`: y\n"]),
+            status
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_described_files() {
+        let draft = "[3] (fix on login)\n\n- `src/`:\n\n\tMove.\n\n- `old.rs -> new.rs`:\n\n\tRename.\n\n- `b.rs`: deleted\n";
+
+        assert_eq!(described_files(draft), vec!["old.rs", "new.rs", "b.rs"]);
+    }
+}
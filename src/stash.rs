@@ -0,0 +1,194 @@
+///
+/// # `stash.rs`
+/// Contains the functions of the `stash` command group.
+// Imports ================================================================================= Imports
+use dialoguer::Select;
+
+use crate::git_related::{get_stash_files, read_stash_list};
+use crate::my_theme::ColorfulTheme;
+
+// Constants  ===========================================================================  Constants
+/// Number of files named in the stash summary
+const SUMMARY_FILES: usize = 3;
+
+// Structs  ===============================================================================  Structs
+/// A stash entry.
+#[derive(Debug, PartialEq)]
+pub struct StashEntry {
+    /// The reference (`stash@{0}`)
+    pub reference: String,
+    /// The relative age (`2 hours ago`)
+    pub age: String,
+    /// The branch the changes were stashed from
+    pub branch: String,
+    /// The stash message
+    pub message: String,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `parse_stash_list`
+/// Parses the output of `read_stash_list`.
+///
+/// ## Arguments
+/// * `output` - `&str` - The stash list
+///
+/// ## Returns
+/// * `Vec<StashEntry>` - The entries, newest first
+pub fn parse_stash_list(output: &str) -> Vec<StashEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\x1f');
+            let reference = fields.next()?.to_string();
+            let age = fields.next()?.to_string();
+            let subject = fields.next()?;
+
+            // `WIP on main: 1a2b3c4 Subject` or `On main: message`
+            let (branch, message) = subject
+                .strip_prefix("WIP on ")
+                .or_else(|| subject.strip_prefix("On "))
+                .and_then(|rest| rest.split_once(": "))
+                .map_or((String::new(), subject.to_string()), |(branch, message)| {
+                    (branch.to_string(), message.to_string())
+                });
+
+            Some(StashEntry {
+                reference,
+                age,
+                branch,
+                message,
+            })
+        })
+        .collect()
+}
+
+///
+/// # `summarize_files`
+/// Summarizes a list of files: the first ones, then the number of the others.
+///
+/// ## Arguments
+/// * `files` - `&[String]` - The files
+///
+/// ## Returns
+/// * `String` - The summary (`a.rs, b.rs, c.rs +2`)
+pub fn summarize_files(files: &[String]) -> String {
+    let mut summary = files
+        .iter()
+        .take(SUMMARY_FILES)
+        .cloned()
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    if files.len() > SUMMARY_FILES {
+        summary.push_str(&format!(" +{}", files.len() - SUMMARY_FILES));
+    }
+
+    summary
+}
+
+///
+/// # `describe_entry`
+/// Describes a stash entry on one line.
+///
+/// ## Arguments
+/// * `entry` - `&StashEntry` - The entry
+///
+/// ## Returns
+/// * `String` - The description
+fn describe_entry(entry: &StashEntry) -> String {
+    let files = get_stash_files(&entry.reference);
+
+    format!(
+        "{} [{}] {} - {} ({} file(s): {})",
+        entry.reference,
+        entry.branch,
+        entry.age,
+        entry.message,
+        files.len(),
+        summarize_files(&files)
+    )
+}
+
+///
+/// # `print_stash_list`
+/// Prints the stash entries with their branch, age and files.
+pub fn print_stash_list() {
+    let entries = parse_stash_list(&read_stash_list());
+
+    if entries.is_empty() {
+        println!("No stash entry.");
+    }
+
+    for entry in entries {
+        println!("{}", describe_entry(&entry));
+    }
+}
+
+///
+/// # `pick_stash`
+/// Lets the user pick a stash entry.
+///
+/// ## Arguments
+/// * `prompt` - `&str` - The picker prompt
+///
+/// ## Returns
+/// * `Option<String>` - The reference of the entry, `None` if there is no entry
+pub fn pick_stash(prompt: &str) -> Option<String> {
+    let mut entries = parse_stash_list(&read_stash_list());
+
+    if entries.is_empty() {
+        return None;
+    }
+
+    let items: Vec<String> = entries.iter().map(describe_entry).collect();
+
+    let index = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(0)
+        .items(&items)
+        .interact()
+        .unwrap();
+
+    Some(entries.swap_remove(index).reference)
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use super::{parse_stash_list, summarize_files, StashEntry};
+
+    #[test]
+    fn test_parse_stash_list() {
+        let output = "stash@{0}\x1f2 hours ago\x1fOn login: half done form\nstash@{1}\x1f3 days ago\x1fWIP on main: 1a2b3c4 [3] (feat on main) Add\n";
+
+        assert_eq!(
+            parse_stash_list(output),
+            vec![
+                StashEntry {
+                    reference: "stash@{0}".to_string(),
+                    age: "2 hours ago".to_string(),
+                    branch: "login".to_string(),
+                    message: "half done form".to_string(),
+                },
+                StashEntry {
+                    reference: "stash@{1}".to_string(),
+                    age: "3 days ago".to_string(),
+                    branch: "main".to_string(),
+                    message: "1a2b3c4 [3] (feat on main) Add".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summarize_files() {
+        let files: Vec<String> = ["a.rs", "b.rs", "c.rs", "d.rs", "e.rs"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(summarize_files(&files), "a.rs, b.rs, c.rs +2");
+        assert_eq!(summarize_files(&files[..1]), "a.rs");
+    }
+}
@@ -0,0 +1,208 @@
+///
+/// # `stats.rs`
+/// Contains the functions used by the `stats` subcommand.
+///
+/// The commit headers of a range are parsed to show how the work divides
+/// between the commit types, the authors, the branches and the months.
+// Imports ================================================================================= Imports
+use std::collections::{BTreeMap, HashMap};
+
+use ansi_term::Colour::Green;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::draft::parse_header;
+use crate::git_related::LogEntry;
+
+// Structs ================================================================================= Structs
+/// The statistics of a range of commits.
+#[derive(Debug, Default, Serialize)]
+pub struct Stats {
+    /// Number of commits
+    pub commits: usize,
+    /// Commits per type (`other` for the unparsable headers)
+    pub by_type: BTreeMap<String, usize>,
+    /// Commits per author
+    pub by_author: BTreeMap<String, usize>,
+    /// Commits per branch, for the headers written by this tool
+    pub by_branch: BTreeMap<String, usize>,
+    /// Commits per month (`YYYY-MM`)
+    pub by_month: BTreeMap<String, usize>,
+    /// Share of the commits following the configured format, between 0 and 1
+    pub format_share: f64,
+    /// Average number of files per commit
+    pub average_files: f64,
+    /// The most often changed files and their number of commits
+    pub top_files: Vec<(String, usize)>,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `compute_stats`
+/// Computes the statistics of some commits.
+///
+/// ## Arguments
+/// * `commits` - `&[LogEntry]` - The commits
+/// * `header_rule` - `&Regex` - The configured header format
+/// * `top` - `usize` - Number of most often changed files to keep
+///
+/// ## Returns
+/// * `Stats` - The statistics
+#[allow(clippy::cast_precision_loss)]
+pub fn compute_stats(commits: &[LogEntry], header_rule: &Regex, top: usize) -> Stats {
+    let mut stats = Stats {
+        commits: commits.len(),
+        ..Stats::default()
+    };
+
+    if commits.is_empty() {
+        return stats;
+    }
+
+    let mut following_format = 0;
+    let mut files_count = 0;
+    let mut files: HashMap<&str, usize> = HashMap::new();
+
+    for commit in commits {
+        let first_line = commit.message.lines().next().unwrap_or_default();
+        let header = parse_header(first_line);
+
+        let commit_type = header
+            .as_ref()
+            .map_or("other".to_string(), |header| header.commit_type.clone());
+        *stats.by_type.entry(commit_type).or_default() += 1;
+
+        if let Some(branch) = header.and_then(|header| header.branch) {
+            *stats.by_branch.entry(branch).or_default() += 1;
+        }
+
+        *stats.by_author.entry(commit.author.clone()).or_default() += 1;
+
+        let month = commit.date.get(..7).unwrap_or(&commit.date).to_string();
+        *stats.by_month.entry(month).or_default() += 1;
+
+        if header_rule.is_match(first_line) {
+            following_format += 1;
+        }
+
+        files_count += commit.files.len();
+        for file in &commit.files {
+            *files.entry(file).or_default() += 1;
+        }
+    }
+
+    stats.format_share = f64::from(following_format) / commits.len() as f64;
+    stats.average_files = files_count as f64 / commits.len() as f64;
+
+    let mut top_files: Vec<(String, usize)> = files
+        .into_iter()
+        .map(|(file, count)| (file.to_string(), count))
+        .collect();
+    top_files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top_files.truncate(top);
+
+    stats.top_files = top_files;
+
+    stats
+}
+
+///
+/// # `print_counts`
+/// Prints a titled table of counts, sorted by decreasing count.
+///
+/// ## Arguments
+/// * `title` - `&str` - The table title
+/// * `counts` - `&BTreeMap<String, usize>` - The counts
+/// * `total` - `usize` - The total, used for the percentages
+#[allow(clippy::cast_precision_loss)]
+fn print_counts(title: &str, counts: &BTreeMap<String, usize>, total: usize) {
+    if counts.is_empty() {
+        return;
+    }
+
+    println!("\n{}", Green.bold().paint(title));
+
+    let mut counts: Vec<(&String, &usize)> = counts.iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(a.1));
+
+    let width = counts.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+
+    for (key, count) in counts {
+        println!(
+            "  {key:<width$}  {count:>5}  {:>5.1}%",
+            *count as f64 * 100.0 / total as f64
+        );
+    }
+}
+
+///
+/// # `print_stats`
+/// Prints the statistics as tables.
+///
+/// ## Arguments
+/// * `stats` - `&Stats` - The statistics
+pub fn print_stats(stats: &Stats) {
+    println!("{} commit(s)", stats.commits);
+
+    if stats.commits == 0 {
+        return;
+    }
+
+    print_counts("By type", &stats.by_type, stats.commits);
+    print_counts("By author", &stats.by_author, stats.commits);
+    print_counts("By branch", &stats.by_branch, stats.commits);
+
+    // Months are printed in chronological order
+    println!("\n{}", Green.bold().paint("By month"));
+    for (month, count) in &stats.by_month {
+        println!("  {month}  {count:>5}");
+    }
+
+    println!("\nFollowing the format: {:.1}%", stats.format_share * 100.0);
+    println!("Average files per commit: {:.1}", stats.average_files);
+
+    if !stats.top_files.is_empty() {
+        println!("\n{}", Green.bold().paint("Most changed files"));
+
+        for (file, count) in &stats.top_files {
+            println!("  {count:>5}  {file}");
+        }
+    }
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use super::compute_stats;
+    use crate::config::FormatConfig;
+    use crate::git_related::LogEntry;
+
+    #[test]
+    fn test_compute_stats() {
+        let commits = vec![
+            LogEntry::sample(
+                "[3] (feat on login) Add",
+                "Tom",
+                "2024-02-03",
+                &["a.rs", "b.rs"],
+            ),
+            LogEntry::sample("[2] (fix on login) Fix", "Ana", "2024-01-20", &["a.rs"]),
+            LogEntry::sample("Quick fix", "Tom", "2024-01-02", &["a.rs", "c.rs", "d.rs"]),
+        ];
+        let header_rule = Regex::new(&FormatConfig::default().header_pattern).unwrap();
+
+        let stats = compute_stats(&commits, &header_rule, 1);
+
+        assert_eq!(stats.commits, 3);
+        assert_eq!(stats.by_type["feat"], 1);
+        assert_eq!(stats.by_type["other"], 1);
+        assert_eq!(stats.by_author["Tom"], 2);
+        assert_eq!(stats.by_branch["login"], 2);
+        assert_eq!(stats.by_month["2024-01"], 2);
+        assert!((stats.format_share - 2.0 / 3.0).abs() < f64::EPSILON);
+        assert!((stats.average_files - 2.0).abs() < f64::EPSILON);
+        assert_eq!(stats.top_files, vec![("a.rs".to_string(), 3)]);
+    }
+}
@@ -0,0 +1,344 @@
+///
+/// # `symbols.rs`
+/// Pre-fills the draft bullets with what changed structurally in each file.
+///
+/// The Rust files are parsed with `syn` before and after the change, and their
+/// functions, structs, enums, traits and impls are compared. The other files fall
+/// back to the function names git writes in the hunk headers.
+// Imports ================================================================================= Imports
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use syn::{ImplItem, Item, Type};
+
+use crate::diffstat::parse_raw;
+use crate::git_related::{read_added_diff, read_blob, read_diff_summary};
+
+// Structs  ===============================================================================  Structs
+/// The definition of a symbol, compared to tell if it was modified.
+#[derive(PartialEq)]
+enum Definition {
+    /// A top-level item, an impl without its methods
+    Item(Item),
+    /// A method of an impl
+    Method(syn::ImplItemFn),
+}
+
+/// A symbol of a Rust file.
+struct Symbol {
+    /// `fn`, `struct`, `enum`, `trait` or `impl`
+    kind: &'static str,
+    /// The name, with its module and type (`tests::test_parse`, `Config::load`)
+    name: String,
+    /// The definition
+    definition: Definition,
+}
+
+// Functions  ===========================================================================  Functions
+///
+/// # `type_name`
+/// Returns the short name of a type.
+///
+/// ## Arguments
+/// * `ty` - `&Type` - The type
+///
+/// ## Returns
+/// * `String` - The last segment of its path (`Config` for `crate::config::Config<'a>`)
+fn type_name(ty: &Type) -> String {
+    match ty {
+        Type::Path(type_path) => path_name(&type_path.path),
+        Type::Reference(reference) => type_name(&reference.elem),
+        _ => "_".to_string(),
+    }
+}
+
+///
+/// # `path_name`
+/// Returns the last segment of a path.
+///
+/// ## Arguments
+/// * `path` - `&syn::Path` - The path
+///
+/// ## Returns
+/// * `String` - The last segment identifier
+fn path_name(path: &syn::Path) -> String {
+    path.segments
+        .last()
+        .map_or_else(|| "_".to_string(), |segment| segment.ident.to_string())
+}
+
+///
+/// # `collect_symbols`
+/// Collects the symbols of items, the inline modules included.
+///
+/// ## Arguments
+/// * `items` - `&[Item]` - The items
+/// * `prefix` - `&str` - The module path of the items (`tests::`)
+/// * `symbols` - `&mut Vec<Symbol>` - The collected symbols
+fn collect_symbols(items: &[Item], prefix: &str, symbols: &mut Vec<Symbol>) {
+    for item in items {
+        let (kind, name) = match item {
+            Item::Fn(function) => ("fn", function.sig.ident.to_string()),
+            Item::Struct(structure) => ("struct", structure.ident.to_string()),
+            Item::Enum(enumeration) => ("enum", enumeration.ident.to_string()),
+            Item::Trait(definition) => ("trait", definition.ident.to_string()),
+            Item::Mod(module) => {
+                if let Some((_, items)) = &module.content {
+                    collect_symbols(items, &format!("{prefix}{}::", module.ident), symbols);
+                }
+                continue;
+            }
+            Item::Impl(implementation) => {
+                let self_name = type_name(&implementation.self_ty);
+
+                // The methods are compared one by one, the impl without them
+                let mut without_methods = implementation.clone();
+                without_methods
+                    .items
+                    .retain(|impl_item| !matches!(impl_item, ImplItem::Fn(_)));
+
+                for impl_item in &implementation.items {
+                    if let ImplItem::Fn(method) = impl_item {
+                        symbols.push(Symbol {
+                            kind: "fn",
+                            name: format!("{prefix}{self_name}::{}", method.sig.ident),
+                            definition: Definition::Method(method.clone()),
+                        });
+                    }
+                }
+
+                let name = match &implementation.trait_ {
+                    Some((_, trait_path, _)) => {
+                        format!("{} for {self_name}", path_name(trait_path))
+                    }
+                    None => self_name,
+                };
+
+                symbols.push(Symbol {
+                    kind: "impl",
+                    name: format!("{prefix}{name}"),
+                    definition: Definition::Item(Item::Impl(without_methods)),
+                });
+                continue;
+            }
+            _ => continue,
+        };
+
+        symbols.push(Symbol {
+            kind,
+            name: format!("{prefix}{name}"),
+            definition: Definition::Item(item.clone()),
+        });
+    }
+}
+
+///
+/// # `diff_symbols`
+/// Compares the symbols of two versions of a Rust file.
+///
+/// ## Arguments
+/// * `old` - `&str` - The content before the change, empty for a new file
+/// * `new` - `&str` - The content after the change
+///
+/// ## Returns
+/// * `Option<Vec<String>>` - The changes (``added fn `parse` ``), `None` if a version doesn't parse
+pub fn diff_symbols(old: &str, new: &str) -> Option<Vec<String>> {
+    let mut old_symbols: Vec<Symbol> = Vec::new();
+    let mut new_symbols: Vec<Symbol> = Vec::new();
+    collect_symbols(&syn::parse_file(old).ok()?.items, "", &mut old_symbols);
+    collect_symbols(&syn::parse_file(new).ok()?.items, "", &mut new_symbols);
+
+    let find = |symbols: &'_ [Symbol], wanted: &Symbol| {
+        symbols
+            .iter()
+            .position(|symbol| symbol.kind == wanted.kind && symbol.name == wanted.name)
+    };
+
+    let mut changes: Vec<String> = Vec::new();
+
+    for symbol in &new_symbols {
+        match find(&old_symbols, symbol) {
+            None => changes.push(format!("added {} `{}`", symbol.kind, symbol.name)),
+            Some(index) if old_symbols[index].definition != symbol.definition => {
+                changes.push(format!("modified {} `{}`", symbol.kind, symbol.name));
+            }
+            Some(_) => {}
+        }
+    }
+
+    for symbol in &old_symbols {
+        if find(&new_symbols, symbol).is_none() {
+            changes.push(format!("removed {} `{}`", symbol.kind, symbol.name));
+        }
+    }
+
+    Some(changes)
+}
+
+///
+/// # `hunk_contexts`
+/// Returns the function names git wrote in the hunk headers of a diff.
+///
+/// ## Arguments
+/// * `diff` - `&str` - The diff
+///
+/// ## Returns
+/// * `Vec<String>` - The contexts (`fn main()`), without duplicates
+pub fn hunk_contexts(diff: &str) -> Vec<String> {
+    let hunk_header = Regex::new(r"^@@ [^@]+ @@ ?(.*)$").unwrap();
+    let mut contexts: Vec<String> = Vec::new();
+
+    for captures in diff.lines().filter_map(|line| hunk_header.captures(line)) {
+        let context = captures[1].trim().to_string();
+
+        if !context.is_empty() && !contexts.contains(&context) {
+            contexts.push(context);
+        }
+    }
+
+    contexts
+}
+
+///
+/// # `format_hint`
+/// Writes the changes as one sentence.
+///
+/// ## Arguments
+/// * `changes` - `&[String]` - The changes
+///
+/// ## Returns
+/// * `String` - The sentence (``Added fn `parse`, removed struct `Old`.``)
+fn format_hint(changes: &[String]) -> String {
+    let sentence = changes.join(", ");
+    let mut characters = sentence.chars();
+
+    match characters.next() {
+        Some(first) => format!("{}{}.", first.to_uppercase(), characters.as_str()),
+        None => String::new(),
+    }
+}
+
+///
+/// # `fill_placeholders`
+/// Writes the hints in the blank placeholder line under the bullets of their files.
+///
+/// ## Arguments
+/// * `draft` - `&str` - The draft
+/// * `hints` - `&BTreeMap<String, String>` - The hints, by file
+///
+/// ## Returns
+/// * `String` - The draft with the hints
+pub fn fill_placeholders(draft: &str, hints: &BTreeMap<String, String>) -> String {
+    let bullet_rule = Regex::new(r"^- `([^`]+)`:").unwrap();
+    let mut filled = String::new();
+    let mut pending: Option<&String> = None;
+
+    for line in draft.lines() {
+        if let Some(captures) = bullet_rule.captures(line) {
+            let path = captures[1].rsplit(" -> ").next().unwrap_or_default();
+            pending = hints.get(path);
+        } else if let Some(hint) = pending.filter(|_| !line.is_empty() && line.trim().is_empty()) {
+            // The tab-indented placeholder
+            filled.push_str(&format!("\t{hint}\n"));
+            pending = None;
+            continue;
+        }
+
+        filled.push_str(line);
+        filled.push('\n');
+    }
+
+    filled
+}
+
+///
+/// # `staged_hints`
+/// Computes the hints of the staged files.
+///
+/// ## Returns
+/// * `BTreeMap<String, String>` - The hints, by file
+pub fn staged_hints() -> BTreeMap<String, String> {
+    let mut hints: BTreeMap<String, String> = BTreeMap::new();
+
+    for entry in parse_raw(&read_diff_summary(&["--cached", "--raw", "-z", "-M"])) {
+        if entry.status == 'D' || entry.new_mode == "160000" {
+            continue;
+        }
+
+        let symbol_changes = if entry.path.ends_with(".rs") {
+            let old_path = entry.old_path.as_deref().unwrap_or(&entry.path);
+            let old = read_blob(&format!("HEAD:{old_path}")).unwrap_or_default();
+            let new = read_blob(&format!(":{}", entry.path)).unwrap_or_default();
+
+            diff_symbols(&old, &new)
+        } else {
+            None
+        };
+
+        let hint = match symbol_changes {
+            Some(changes) => format_hint(&changes),
+            None => {
+                let contexts = hunk_contexts(&read_added_diff(&["--cached", "--", &entry.path]));
+                let contexts: Vec<String> = contexts
+                    .iter()
+                    .map(|context| format!("`{context}`"))
+                    .collect();
+
+                if contexts.is_empty() {
+                    String::new()
+                } else {
+                    format!("Changed in {}.", contexts.join(", "))
+                }
+            }
+        };
+
+        if !hint.is_empty() {
+            hints.insert(entry.path, hint);
+        }
+    }
+
+    hints
+}
+
+// Tests ==================================================================================== Tests
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{diff_symbols, fill_placeholders, hunk_contexts};
+
+    #[test]
+    fn test_diff_symbols() {
+        let old = "struct Config { a: u8 }\nimpl Config {\n    fn load() {}\n    fn save() {}\n}\nfn old() {}\n";
+        let new = "struct Config { a: u8, b: u8 }\nimpl Config {\n    fn load() {}\n    fn save() { todo!() }\n}\nenum Mode { A }\n";
+
+        assert_eq!(
+            diff_symbols(old, new),
+            Some(vec![
+                "modified struct `Config`".to_string(),
+                "modified fn `Config::save`".to_string(),
+                "added enum `Mode`".to_string(),
+                "removed fn `old`".to_string(),
+            ])
+        );
+        assert_eq!(diff_symbols("fn main() {", "fn main() {}"), None);
+    }
+
+    #[test]
+    fn test_hunk_contexts() {
+        let diff = "@@ -3,0 +4 @@ def load(path):\n+    pass\n@@ -9 +10 @@ def load(path):\n@@ -20 +21 @@\n";
+
+        assert_eq!(hunk_contexts(diff), vec!["def load(path):"]);
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        let draft = "[3] (feat on login)\n\n- `a.rs`:\n# staged +1 -0\n\n\t\n\n- `b.rs`:\n\n\t\n";
+        let hints = BTreeMap::from([("a.rs".to_string(), "Added fn `parse`.".to_string())]);
+
+        assert_eq!(
+            fill_placeholders(draft, &hints),
+            "[3] (feat on login)\n\n- `a.rs`:\n# staged +1 -0\n\n\tAdded fn `parse`.\n\n- `b.rs`:\n\n\t\n"
+        );
+    }
+}
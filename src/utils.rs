@@ -64,6 +64,21 @@ pub fn read_file(path: &Path) -> String {
     std::fs::read_to_string(path).expect("Something went wrong reading the file")
 }
 
+///
+/// # `open_in_editor`
+/// Opens a file in Zed and waits for it to be closed.
+///
+/// ## Arguments
+/// * `path` - `&Path` - The file
+pub fn open_in_editor(path: &Path) {
+    // Open the file in Zed.
+    let _ = std::process::Command::new("zed")
+        .arg(path)
+        .spawn()
+        .expect("Error opening the file in Zed")
+        .wait();
+}
+
 // Tests ==================================================================================== Tests
 #[cfg(test)]
 mod tests {